@@ -0,0 +1,264 @@
+use crate::run_all::isolate;
+use crate::runner::{parse_day, AnswersOpts, Day, Part, Runner};
+use crate::solution;
+use crate::table::{table, Format};
+use anyhow::{anyhow, bail, Result};
+use scraper::{Html, Selector};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::time::sleep;
+
+/// Answers the site accepted, in part order, as shown on a solved puzzle page.
+pub fn scrape(html: &str) -> Vec<String> {
+    let document = Html::parse_document(html);
+    let paragraphs = Selector::parse("main > p").unwrap();
+    let code = Selector::parse("code").unwrap();
+    document
+        .select(&paragraphs)
+        .filter(|p| {
+            p.text()
+                .collect::<String>()
+                .starts_with("Your puzzle answer was")
+        })
+        .filter_map(|p| Some(p.select(&code).next()?.text().collect()))
+        .collect()
+}
+
+fn answers_path(runner: &Runner) -> PathBuf {
+    runner.cache_dir().join("answers.tsv")
+}
+
+fn load(path: &Path) -> Result<Vec<(Day, Part, String)>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    fs::read_to_string(path)?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let fields = line.splitn(3, '\t').collect::<Vec<_>>();
+            let [day, part, answer] = fields[..] else {
+                bail!("Invalid answers line: {line}");
+            };
+            Ok((
+                parse_day(day).map_err(|e| anyhow!(e))?,
+                part.parse().map_err(|e: &str| anyhow!(e))?,
+                answer.to_string(),
+            ))
+        })
+        .collect()
+}
+
+/// Downloads the puzzle page of every implemented day and records the answers
+/// accepted so far. Days that can't be fetched keep the answers recorded before.
+pub async fn fetch(runner: &Runner, opts: AnswersOpts) -> Result<()> {
+    let path = answers_path(runner);
+    let recorded = load(&path)?;
+    let submissions = runner.submissions()?;
+    let mut answers = Vec::new();
+    let mut failures = 0;
+    let days = Day::all().filter(|day| solution::find(runner.year(), *day).is_ok());
+    for (i, day) in days.enumerate() {
+        if i > 0 {
+            sleep(Duration::from_secs(opts.delay)).await;
+        }
+        let html = runner.get_puzzle(day, true).await;
+        if let Err(error) = &html {
+            eprintln!("Day {}: {error:#}", day.day_number());
+        }
+        let failed = html.is_err();
+        let scraped = html.map(|html| scrape(&html)).unwrap_or_default();
+        // A solved part without an answer means the page isn't the one expected
+        let missing = [Part::One, Part::Two]
+            .into_iter()
+            .skip(scraped.len())
+            .find(|part| submissions.is_solved(day, *part));
+        if let Some(part) = missing {
+            eprintln!(
+                "Day {}: part {} is solved but the page shows no answer for it",
+                day.day_number(),
+                part.part_number()
+            );
+        }
+        if failed || missing.is_some() {
+            failures += 1;
+            let kept = recorded
+                .iter()
+                .filter(|(answer_day, _, _)| *answer_day == day);
+            answers.extend(kept.cloned());
+            continue;
+        }
+        answers.extend(
+            [Part::One, Part::Two]
+                .into_iter()
+                .zip(scraped)
+                .map(|(part, answer)| (day, part, answer)),
+        );
+    }
+
+    if answers.is_empty() {
+        println!("No answers to write to {}", path.display());
+    } else {
+        let lines = answers
+            .iter()
+            .map(|(day, part, answer)| {
+                format!("{}\t{}\t{answer}", day.day_number(), part.part_number())
+            })
+            .collect::<Vec<_>>();
+        fs::create_dir_all(runner.cache_dir())?;
+        fs::write(&path, lines.join("\n") + "\n")?;
+        println!("{} answers written to {}", lines.len(), path.display());
+    }
+    if failures > 0 {
+        bail!("{failures} days failed, the answers recorded for them before were kept");
+    }
+    Ok(())
+}
+
+/// Runs every implemented solution with a recorded answer on its real input and
+/// fails if any of them no longer gets it right.
+pub async fn verify(runner: &Runner, format: Format) -> Result<()> {
+    let path = answers_path(runner);
+    let answers = load(&path)?;
+    if answers.is_empty() {
+        bail!(
+            "No answers recorded in {}, fetch them with the answers command",
+            path.display()
+        );
+    }
+
+    let mut rows = Vec::new();
+    let mut failures = 0;
+    for day in Day::all() {
+        let expected = answers
+            .iter()
+            .filter(|(answer_day, _, _)| *answer_day == day)
+            .collect::<Vec<_>>();
+        let Ok(parse) = solution::find(runner.year(), day) else {
+            continue;
+        };
+        if expected.is_empty() {
+            continue;
+        }
+
+        // A missing input fails the day's rows rather than the whole run
+        let input = match runner.get_input(day, false).await {
+            Ok(lines) => isolate(|| parse(lines)),
+            Err(error) => Err(error),
+        };
+        for (_, part, answer) in expected {
+            let actual = match &input {
+                Ok(input) => isolate(|| input.solve(*part)),
+                Err(error) => Err(anyhow!("{error:#}")),
+            };
+            let (actual, status) = match actual {
+                Ok(actual) if actual == *answer => (actual, "ok"),
+                Ok(actual) => (actual, "WRONG"),
+                Err(error) => (format!("error: {error:#}"), "FAILED"),
+            };
+            if status != "ok" {
+                failures += 1;
+            }
+            rows.push(vec![
+                day.day_number().to_string(),
+                part.part_number().to_string(),
+                answer.clone(),
+                actual,
+                status.to_string(),
+            ]);
+        }
+    }
+
+    println!();
+    println!(
+        "{}",
+        table(
+            format,
+            &["Day", "Part", "Expected", "Actual", "Status"],
+            &rows
+        )
+    );
+    if failures > 0 {
+        bail!("{failures} of {} answers are no longer right", rows.len());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::tests::{day, runner, stand_in};
+    use crate::submission::{Submission, Verdict};
+
+    #[test]
+    fn scraped() {
+        let html = include_str!("../tests/fixtures/puzzle/day_1.html");
+        assert_eq!(scrape(html), vec!["69883", "207576"]);
+    }
+
+    #[tokio::test]
+    async fn writes_nothing_when_every_day_fails() {
+        let (url, _) = stand_in(&[]);
+        let runner = runner("answers-offline", url, true);
+
+        let error = fetch(&runner, AnswersOpts { delay: 0 }).await.unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "6 days failed, the answers recorded for them before were kept"
+        );
+        let path = answers_path(&runner);
+        assert!(!path.exists());
+        assert!(load(&path).unwrap().is_empty());
+
+        fs::write(&path, "\n1\t1\t24000\n\n").unwrap();
+        assert_eq!(load(&path).unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn missing_input_fails_its_rows() {
+        let (url, _) = stand_in(&[]);
+        let runner = runner("verify-offline", url, true);
+        fs::create_dir_all(runner.cache_dir()).unwrap();
+        fs::write(answers_path(&runner), "1\t1\t24000\n2\t1\t15\n").unwrap();
+        fs::write(runner.input_path(day("2")), "A Y\nB X\nC Z\n").unwrap();
+
+        let error = verify(&runner, Format::Tsv).await.unwrap_err();
+        assert_eq!(error.to_string(), "1 of 2 answers are no longer right");
+    }
+
+    #[tokio::test]
+    async fn keeps_answers_of_failed_days() {
+        let (url, _) = stand_in(&[
+            (
+                "/2022/day/1",
+                include_str!("../tests/fixtures/puzzle/day_1.html"),
+            ),
+            (
+                "/2022/day/2",
+                "<main><article class=\"day-desc\"></article></main>",
+            ),
+        ]);
+        let runner = runner("answers", url, false);
+        let path = answers_path(&runner);
+        fs::create_dir_all(runner.cache_dir()).unwrap();
+        fs::write(&path, "2\t1\t15\n3\t1\t157\n").unwrap();
+        // Solved, so its page must show an answer
+        let solved = Submission::new(day("2"), Part::One, "15".to_string(), Verdict::Correct);
+        runner
+            .submissions()
+            .unwrap()
+            .record(solved.unwrap())
+            .unwrap();
+
+        let error = fetch(&runner, AnswersOpts { delay: 0 }).await.unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "5 days failed, the answers recorded for them before were kept"
+        );
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "1\t1\t69883\n1\t2\t207576\n2\t1\t15\n3\t1\t157\n"
+        );
+    }
+}
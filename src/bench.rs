@@ -0,0 +1,352 @@
+use crate::runner::{parse_day, parse_year, BenchOpts, Day, Part, Runner, Year};
+use crate::solution;
+use crate::table::{table, Format};
+use crate::Lines;
+use anyhow::{anyhow, bail, ensure, Result};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::process;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    pub fn new(mut samples: Vec<Duration>) -> Self {
+        samples.sort();
+        let n = samples.len() as f64;
+        let mean = samples.iter().map(Duration::as_secs_f64).sum::<f64>() / n;
+        let variance = samples
+            .iter()
+            .map(|sample| (sample.as_secs_f64() - mean).powi(2))
+            .sum::<f64>()
+            / n;
+        let middle = samples.len() / 2;
+        let median = if samples.len().is_multiple_of(2) {
+            (samples[middle - 1] + samples[middle]) / 2
+        } else {
+            samples[middle]
+        };
+
+        Self {
+            min: samples[0],
+            median,
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+fn time<T>(iterations: usize, mut f: impl FnMut() -> Result<T>) -> Result<Vec<Duration>> {
+    let mut samples = Vec::with_capacity(iterations);
+    for _ in 0..iterations {
+        let start = Instant::now();
+        f()?;
+        samples.push(start.elapsed());
+    }
+    Ok(samples)
+}
+
+fn lines(input: &[String]) -> Lines {
+    let input = input.to_vec();
+    Lines::new(Box::new(input.into_iter()))
+}
+
+#[derive(Clone, Copy)]
+struct Measurement {
+    day: Day,
+    // `None` for the parse phase, which is shared by both parts
+    part: Option<Part>,
+    stats: Stats,
+}
+
+impl Measurement {
+    fn row(&self) -> Vec<String> {
+        let (part, phase) = match self.part {
+            Some(part) => (part.part_number().to_string(), "solve"),
+            None => ("-".to_string(), "parse"),
+        };
+        vec![
+            self.day.day_number().to_string(),
+            part,
+            phase.to_string(),
+            format!("{:.2?}", self.stats.min),
+            format!("{:.2?}", self.stats.median),
+            format!("{:.2?}", self.stats.mean),
+            format!("{:.2?}", self.stats.stddev),
+        ]
+    }
+}
+
+async fn bench_day(
+    runner: &Runner,
+    day: Day,
+    parse: solution::Parser,
+    opts: BenchOpts,
+) -> Result<Vec<Measurement>> {
+    let input = runner.get_input(day, false).await?.collect::<Vec<_>>();
+
+    // Building `Lines` copies the input, which shouldn't count towards parsing
+    let mut parse_samples = Vec::with_capacity(opts.iterations);
+    for i in 0..opts.warmup + opts.iterations {
+        let lines = lines(&input);
+        let start = Instant::now();
+        parse(lines)?;
+        if i >= opts.warmup {
+            parse_samples.push(start.elapsed());
+        }
+    }
+    let mut measurements = vec![Measurement {
+        day,
+        part: None,
+        stats: Stats::new(parse_samples),
+    }];
+
+    let parsed = parse(lines(&input))?;
+    for part in opts.part.parts() {
+        if !parsed.is_implemented(part) {
+            continue;
+        }
+        time(opts.warmup, || parsed.solve(part))?;
+        let samples = time(opts.iterations, || parsed.solve(part))?;
+        measurements.push(Measurement {
+            day,
+            part: Some(part),
+            stats: Stats::new(samples),
+        });
+    }
+    Ok(measurements)
+}
+
+pub async fn bench(runner: &Runner, opts: BenchOpts, format: Format) -> Result<()> {
+    ensure!(opts.iterations > 0, "At least one iteration is needed");
+
+    let solutions = match opts.day {
+        Some(day) => vec![(day, solution::find(runner.year(), day)?)],
+        None => Day::all()
+            .filter_map(|day| Some((day, solution::find(runner.year(), day).ok()?)))
+            .collect(),
+    };
+
+    let mut measurements = Vec::new();
+    for (day, parse) in solutions {
+        println!("Benchmarking day {}", day.day_number());
+        measurements.extend(bench_day(runner, day, parse, opts).await?);
+    }
+
+    let rows = measurements
+        .iter()
+        .map(Measurement::row)
+        .collect::<Vec<_>>();
+    println!();
+    println!(
+        "{}",
+        table(
+            format,
+            &["Day", "Part", "Phase", "Min", "Median", "Mean", "Std dev"],
+            &rows
+        )
+    );
+
+    let history_path = Path::new(HISTORY_PATH);
+    if opts.compare {
+        let history = read_history(history_path)?
+            .into_iter()
+            .filter(|record| record.year == runner.year())
+            .collect::<Vec<_>>();
+        compare(&history, &measurements, opts.threshold, format)
+    } else {
+        record(history_path, runner.year(), &measurements)
+    }
+}
+
+const HISTORY_PATH: &str = "bench-history.tsv";
+
+/// One line of the history file: a measurement tagged with when and on which
+/// commit it was taken, and for which event.
+struct Record {
+    timestamp: u64,
+    commit: Option<String>,
+    year: Year,
+    measurement: Measurement,
+}
+
+impl Record {
+    fn to_line(&self) -> String {
+        let Measurement { day, part, stats } = &self.measurement;
+        [
+            self.timestamp.to_string(),
+            self.commit.clone().unwrap_or_else(|| "-".to_string()),
+            self.year.year_number().to_string(),
+            day.day_number().to_string(),
+            part.map(|part| part.part_number().to_string())
+                .unwrap_or_else(|| "-".to_string()),
+            stats.min.as_nanos().to_string(),
+            stats.median.as_nanos().to_string(),
+            stats.mean.as_nanos().to_string(),
+            stats.stddev.as_nanos().to_string(),
+        ]
+        .join("\t")
+    }
+
+    fn from_line(line: &str) -> Result<Self> {
+        let fields = line.split('\t').collect::<Vec<_>>();
+        let [timestamp, commit, year, day, part, min, median, mean, stddev] = fields[..] else {
+            bail!("Invalid benchmark history line: {line}");
+        };
+        let nanos = |field: &str| -> Result<Duration> { Ok(Duration::from_nanos(field.parse()?)) };
+        Ok(Self {
+            timestamp: timestamp.parse()?,
+            commit: (commit != "-").then(|| commit.to_string()),
+            year: parse_year(year).map_err(|e| anyhow!(e))?,
+            measurement: Measurement {
+                day: parse_day(day).map_err(|e| anyhow!(e))?,
+                part: match part {
+                    "-" => None,
+                    part => Some(part.parse().map_err(|e: &str| anyhow!(e))?),
+                },
+                stats: Stats {
+                    min: nanos(min)?,
+                    median: nanos(median)?,
+                    mean: nanos(mean)?,
+                    stddev: nanos(stddev)?,
+                },
+            },
+        })
+    }
+}
+
+fn read_history(path: &Path) -> Result<Vec<Record>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    fs::read_to_string(path)?
+        .lines()
+        .map(Record::from_line)
+        .collect()
+}
+
+fn current_commit() -> Option<String> {
+    let output = process::Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn record(path: &Path, year: Year, measurements: &[Measurement]) -> Result<()> {
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let commit = current_commit();
+
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    for measurement in measurements {
+        let record = Record {
+            timestamp,
+            commit: commit.clone(),
+            year,
+            measurement: *measurement,
+        };
+        writeln!(file, "{}", record.to_line())?;
+    }
+    println!("Results appended to {}", path.display());
+    Ok(())
+}
+
+/// Compares the medians against the most recent recorded run of the same day and
+/// part, failing when any of them got slower by more than `threshold` percent.
+fn compare(
+    history: &[Record],
+    measurements: &[Measurement],
+    threshold: f64,
+    format: Format,
+) -> Result<()> {
+    let mut rows = Vec::new();
+    let mut regressions = 0;
+    for measurement in measurements {
+        let Some(baseline) = history
+            .iter()
+            .rev()
+            .map(|record| &record.measurement)
+            .find(|baseline| baseline.day == measurement.day && baseline.part == measurement.part)
+        else {
+            continue;
+        };
+
+        let baseline_median = baseline.stats.median.as_secs_f64();
+        let change =
+            (measurement.stats.median.as_secs_f64() - baseline_median) / baseline_median * 100.0;
+        let regressed = change > threshold;
+        if regressed {
+            regressions += 1;
+        }
+
+        let mut row = measurement.row();
+        row.truncate(3);
+        row.extend([
+            format!("{:.2?}", baseline.stats.median),
+            format!("{:.2?}", measurement.stats.median),
+            format!("{change:+.1}%"),
+            if regressed { "REGRESSION" } else { "ok" }.to_string(),
+        ]);
+        rows.push(row);
+    }
+
+    println!();
+    if rows.is_empty() {
+        println!("No baseline recorded in {HISTORY_PATH} yet");
+        return Ok(());
+    }
+    println!(
+        "{}",
+        table(
+            format,
+            &["Day", "Part", "Phase", "Baseline", "Current", "Change", "Status"],
+            &rows
+        )
+    );
+
+    if regressions > 0 {
+        bail!("{regressions} benchmarks got more than {threshold}% slower than the baseline");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats() {
+        let samples = [4, 1, 3, 2]
+            .into_iter()
+            .map(Duration::from_millis)
+            .collect();
+        let stats = Stats::new(samples);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_micros(2500));
+        assert_eq!(stats.mean, Duration::from_micros(2500));
+        assert_eq!(stats.stddev.as_micros(), 1118);
+    }
+
+    #[test]
+    fn record_round_trip() {
+        let line = "1670400000\t7a66da4\t2022\t3\t2\t1000\t2000\t2500\t300";
+        assert_eq!(Record::from_line(line).unwrap().to_line(), line);
+
+        let parse = "1670400000\t-\t2022\t3\t-\t1000\t2000\t2500\t300";
+        let record = Record::from_line(parse).unwrap();
+        assert_eq!(record.commit, None);
+        assert_eq!(record.year, parse_year("2022").unwrap());
+        assert_eq!(record.measurement.part, None);
+        assert_eq!(record.to_line(), parse);
+    }
+}
@@ -0,0 +1,400 @@
+//! Imitates the parts of the Advent of Code site the runner talks to, for local
+//! development and CI. Puzzles come from a fixture directory laid out as:
+//!
+//! - `<year>/day-<N>/input.txt`: the puzzle input
+//! - `<year>/day-<N>/answer-<P>.txt`: the answer accepted for part P
+//! - `<year>/day-<N>/part-<P>.html`: the description of part P, optional
+//! - `<year>/leaderboard/<ID>.json`: a private leaderboard
+//!
+//! Progress and cooldowns are kept in memory per session cookie, so restarting
+//! the server starts every puzzle over.
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use hyper::header::{CONTENT_TYPE, COOKIE};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::fs;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+#[derive(Parser, Debug)]
+#[command(about = "A stand-in for the Advent of Code site, backed by fixtures")]
+struct Opts {
+    /// Directory with the puzzles to serve
+    #[arg(short, long, default_value = "tests/fixtures/mock")]
+    fixtures: PathBuf,
+    #[arg(short, long, default_value_t = 8080)]
+    port: u16,
+    /// Seconds to wait after a wrong answer before another one is accepted
+    #[arg(short, long, default_value_t = 60)]
+    cooldown: u64,
+}
+
+const LOG_IN: &str = "Puzzle inputs differ by user.  Please log in to get your puzzle input.";
+
+/// How far a session got with a puzzle.
+#[derive(Default)]
+struct Progress {
+    solved: u8,
+    locked_until: Option<Instant>,
+}
+
+struct Mock {
+    fixtures: PathBuf,
+    cooldown: Duration,
+    progress: Mutex<HashMap<(String, String, u8), Progress>>,
+}
+
+impl Mock {
+    fn day_dir(&self, year: &str, day: u8) -> PathBuf {
+        self.fixtures.join(year).join(format!("day-{day}"))
+    }
+
+    fn answer(&self, year: &str, day: u8, part: u8) -> Option<String> {
+        let path = self.day_dir(year, day).join(format!("answer-{part}.txt"));
+        Some(fs::read_to_string(path).ok()?.trim().to_string())
+    }
+
+    fn solved(&self, session: Option<&str>, year: &str, day: u8) -> u8 {
+        let Some(session) = session else {
+            return 0;
+        };
+        let progress = self.progress.lock().unwrap();
+        progress
+            .get(&(session.to_string(), year.to_string(), day))
+            .map_or(0, |progress| progress.solved)
+    }
+
+    fn puzzle(&self, session: Option<&str>, year: &str, day: u8) -> Option<String> {
+        let dir = self.day_dir(year, day);
+        if !dir.exists() {
+            return None;
+        }
+
+        let solved = self.solved(session, year, day);
+        let mut main = String::new();
+        for part in 1..=(solved + 1).min(2) {
+            let description = fs::read_to_string(dir.join(format!("part-{part}.html")))
+                .unwrap_or_else(|_| match part {
+                    1 => format!("<h2>--- Day {day} ---</h2><p>No description.</p>"),
+                    _ => "<h2 id=\"part2\">--- Part Two ---</h2><p>No description.</p>".to_string(),
+                });
+            main.push_str(&format!(
+                "<article class=\"day-desc\">{description}</article>\n"
+            ));
+            if part <= solved {
+                let answer = self.answer(year, day, part).unwrap_or_default();
+                main.push_str(&format!(
+                    "<p>Your puzzle answer was <code>{answer}</code>.</p>"
+                ));
+            }
+        }
+        if solved >= 2 {
+            main.push_str("<p class=\"day-success\">Both parts of this puzzle are complete! They provide two gold stars: **</p>\n");
+        }
+        Some(page(year, day, &main))
+    }
+
+    /// Judges an answer the way the site does, returning the message it shows.
+    fn submit(&self, session: &str, year: &str, day: u8, level: u8, answer: &str) -> String {
+        let expected = self.answer(year, day, level);
+        let mut progress = self.progress.lock().unwrap();
+        let progress = progress
+            .entry((session.to_string(), year.to_string(), day))
+            .or_default();
+        let now = Instant::now();
+
+        if let Some(left) = progress
+            .locked_until
+            .and_then(|until| until.checked_duration_since(now))
+        {
+            return format!(
+                "You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have {} left to wait.",
+                format_wait(left)
+            );
+        }
+        let Some(expected) = expected.filter(|_| level == progress.solved + 1) else {
+            return "You don't seem to be solving the right level.  Did you already complete it?"
+                .to_string();
+        };
+        if answer.trim() == expected {
+            progress.solved += 1;
+            return "That's the right answer!  You are <span class=\"day-success\">one gold star</span> closer to collecting enough star fruit.".to_string();
+        }
+
+        progress.locked_until = Some(now + self.cooldown);
+        let hint = match (answer.trim().parse::<i128>(), expected.parse::<i128>()) {
+            (Ok(answer), Ok(expected)) if answer > expected => "; your answer is too high",
+            (Ok(answer), Ok(expected)) if answer < expected => "; your answer is too low",
+            _ => "",
+        };
+        format!(
+            "That's not the right answer{hint}.  Please wait {} before trying again.",
+            format_wait(self.cooldown)
+        )
+    }
+}
+
+fn format_wait(wait: Duration) -> String {
+    // Rounded up, so a cooldown that is about to end still shows as 1s
+    let seconds = wait.as_secs() + u64::from(wait.subsec_nanos() > 0);
+    match seconds / 60 {
+        0 => format!("{seconds}s"),
+        minutes => format!("{minutes}m {}s", seconds % 60),
+    }
+}
+
+fn page(year: &str, day: u8, main: &str) -> String {
+    format!(
+        "<!DOCTYPE html>
+<html lang=\"en-us\">
+<head>
+<meta charset=\"utf-8\"/>
+<title>Day {day} - Advent of Code {year}</title>
+</head>
+<body>
+<main>
+{main}
+</main>
+</body>
+</html>
+"
+    )
+}
+
+fn session(request: &Request<Body>) -> Option<String> {
+    let cookies = request.headers().get(COOKIE)?.to_str().ok()?;
+    cookies
+        .split(';')
+        .find_map(|cookie| cookie.trim().strip_prefix("session="))
+        .map(str::to_string)
+}
+
+fn respond(status: StatusCode, body: impl Into<Body>) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .body(body.into())
+        .unwrap()
+}
+
+fn not_found() -> Response<Body> {
+    respond(StatusCode::NOT_FOUND, "404 Not Found")
+}
+
+fn file(path: &Path, content_type: &str) -> Response<Body> {
+    match fs::read(path) {
+        Ok(content) => Response::builder()
+            .header(CONTENT_TYPE, content_type)
+            .body(content.into())
+            .unwrap(),
+        Err(_) => not_found(),
+    }
+}
+
+async fn handle(mock: Arc<Mock>, request: Request<Body>) -> Response<Body> {
+    let session = session(&request);
+    let method = request.method().clone();
+    let path = request.uri().path().to_string();
+    let segments = path.trim_matches('/').split('/').collect::<Vec<_>>();
+    println!("{method} {path}");
+    // Segments end up in fixture paths, which must not lead out of the directory
+    if segments.iter().any(|segment| segment.starts_with('.')) {
+        return not_found();
+    }
+
+    match (&method, &segments[..]) {
+        (&Method::GET, [year, "day", day]) => {
+            let Ok(day) = day.parse() else {
+                return not_found();
+            };
+            match mock.puzzle(session.as_deref(), year, day) {
+                Some(html) => respond(StatusCode::OK, html),
+                None => not_found(),
+            }
+        }
+        (&Method::GET, [year, "day", day, "input"]) => {
+            if session.is_none() {
+                return respond(StatusCode::BAD_REQUEST, LOG_IN);
+            }
+            file(
+                &mock
+                    .fixtures
+                    .join(year)
+                    .join(format!("day-{day}/input.txt")),
+                "text/plain",
+            )
+        }
+        (&Method::POST, [year, "day", day, "answer"]) => {
+            let (Some(session), Ok(day)) = (session, day.parse()) else {
+                return respond(StatusCode::BAD_REQUEST, LOG_IN);
+            };
+            let body = hyper::body::to_bytes(request.into_body())
+                .await
+                .unwrap_or_default();
+            let form = url::form_urlencoded::parse(&body).collect::<HashMap<_, _>>();
+            let (Some(Ok(level)), Some(answer)) = (
+                form.get("level").map(|level| level.parse()),
+                form.get("answer"),
+            ) else {
+                return respond(StatusCode::BAD_REQUEST, "Missing level or answer");
+            };
+
+            let message = mock.submit(&session, year, day, level, answer);
+            let main = format!(
+                "<article><p>{message} <a href=\"/{year}/day/{day}\">[Return to Day {day}]</a></p></article>"
+            );
+            respond(StatusCode::OK, page(year, day, &main))
+        }
+        (&Method::GET, [year, "leaderboard", "private", "view", id]) => {
+            if session.is_none() {
+                return respond(StatusCode::BAD_REQUEST, LOG_IN);
+            }
+            file(
+                &mock.fixtures.join(year).join("leaderboard").join(id),
+                "application/json",
+            )
+        }
+        _ => not_found(),
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let opts = Opts::parse();
+    let mock = Arc::new(Mock {
+        fixtures: opts.fixtures,
+        cooldown: Duration::from_secs(opts.cooldown),
+        progress: Mutex::default(),
+    });
+
+    let make_service = make_service_fn(move |_| {
+        let mock = mock.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| {
+                let mock = mock.clone();
+                async move { Ok::<_, Infallible>(handle(mock, request).await) }
+            }))
+        }
+    });
+    let address = SocketAddr::from(([127, 0, 0, 1], opts.port));
+    let server = Server::try_bind(&address)
+        .with_context(|| format!("Can't listen on {address}"))?
+        .serve(make_service);
+    println!("Serving on http://{address}");
+    server.await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mock(cooldown: u64) -> Arc<Mock> {
+        Arc::new(Mock {
+            fixtures: PathBuf::from("tests/fixtures/mock"),
+            cooldown: Duration::from_secs(cooldown),
+            progress: Mutex::default(),
+        })
+    }
+
+    async fn get(mock: &Arc<Mock>, path: &str) -> (StatusCode, String) {
+        let request = Request::get(path)
+            .header(COOKIE, "session=test")
+            .body(Body::empty())
+            .unwrap();
+        read(handle(mock.clone(), request).await).await
+    }
+
+    async fn send(mock: &Arc<Mock>, level: u8, answer: &str) -> String {
+        let request = Request::post("/2022/day/1/answer")
+            .header(COOKIE, "session=test")
+            .body(format!("level={level}&answer={answer}").into())
+            .unwrap();
+        read(handle(mock.clone(), request).await).await.1
+    }
+
+    async fn read(response: Response<Body>) -> (StatusCode, String) {
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        (status, String::from_utf8_lossy(&body).to_string())
+    }
+
+    #[tokio::test]
+    async fn serves_fixtures() {
+        let mock = mock(60);
+        let (status, input) = get(&mock, "/2022/day/1/input").await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(input.starts_with("1000\n2000\n"));
+        assert_eq!(
+            get(&mock, "/2022/day/2/input").await.0,
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(get(&mock, "/2022/day/2").await.0, StatusCode::NOT_FOUND);
+
+        let (status, leaderboard) = get(&mock, "/2022/leaderboard/private/view/12345.json").await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(leaderboard.contains("\"owner_id\":12345"));
+
+        let request = Request::get("/2022/day/1/input")
+            .body(Body::empty())
+            .unwrap();
+        let (status, body) = read(handle(mock, request).await).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body, LOG_IN);
+    }
+
+    #[tokio::test]
+    async fn reveals_part_two_once_part_one_is_solved() {
+        let mock = mock(0);
+        let page = get(&mock, "/2022/day/1").await.1;
+        assert_eq!(page.matches("<article class=\"day-desc\">").count(), 1);
+        assert!(!page.contains("Your puzzle answer was"));
+
+        assert!(send(&mock, 1, "24000")
+            .await
+            .contains("That's the right answer"));
+        let page = get(&mock, "/2022/day/1").await.1;
+        assert_eq!(page.matches("<article class=\"day-desc\">").count(), 2);
+        assert!(page.contains("Your puzzle answer was <code>24000</code>."));
+    }
+
+    #[tokio::test]
+    async fn judges_answers() {
+        let mock = mock(0);
+        assert!(send(&mock, 2, "45000")
+            .await
+            .contains("solving the right level"));
+        assert!(send(&mock, 1, "30000")
+            .await
+            .contains("That's not the right answer; your answer is too high."));
+        assert!(send(&mock, 1, "100")
+            .await
+            .contains("That's not the right answer; your answer is too low."));
+        assert!(send(&mock, 1, "CMZ")
+            .await
+            .contains("That's not the right answer."));
+        assert!(send(&mock, 1, "24000")
+            .await
+            .contains("That's the right answer"));
+        assert!(send(&mock, 1, "24000")
+            .await
+            .contains("solving the right level"));
+        assert!(send(&mock, 2, "45000")
+            .await
+            .contains("That's the right answer"));
+    }
+
+    #[tokio::test]
+    async fn enforces_cooldown() {
+        let mock = mock(90);
+        assert!(send(&mock, 1, "1").await.contains("Please wait 1m 30s"));
+        assert!(send(&mock, 1, "24000")
+            .await
+            .contains("You have 1m 30s left to wait."));
+    }
+}
@@ -0,0 +1,500 @@
+use crate::runner::{
+    check_body, parse_year, CacheCommand, ClearOpts, Day, InputError, PrefetchOpts, Runner, Year,
+};
+use crate::table::{table, Format};
+use anyhow::{anyhow, bail, ensure, Result};
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use chrono::{TimeZone, Utc};
+use sha2::{Digest, Sha256};
+use std::ffi::OsString;
+use std::fmt::{Debug, Formatter};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::time::sleep;
+
+/// What gets recorded next to a downloaded file, to tell later whether it's
+/// still what was downloaded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Metadata {
+    pub size: u64,
+    pub sha256: String,
+    pub downloaded: u64,
+}
+
+impl Metadata {
+    fn of(contents: &[u8], downloaded: u64) -> Self {
+        Self {
+            size: contents.len() as u64,
+            sha256: sha256(contents),
+            downloaded,
+        }
+    }
+
+    fn to_line(&self) -> String {
+        format!("{}\t{}\t{}", self.size, self.sha256, self.downloaded)
+    }
+
+    fn from_line(line: &str) -> Result<Self> {
+        let fields = line.trim_end().split('\t').collect::<Vec<_>>();
+        let [size, sha256, downloaded] = fields[..] else {
+            bail!("Invalid cache metadata: {line}");
+        };
+        Ok(Self {
+            size: size.parse()?,
+            sha256: sha256.to_string(),
+            downloaded: downloaded.parse()?,
+        })
+    }
+}
+
+fn sha256(contents: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(contents))
+}
+
+/// `path` with `suffix` added to its file name.
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().map(OsString::from).unwrap_or_default();
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+pub fn metadata_path(path: &Path) -> PathBuf {
+    with_suffix(path, ".meta")
+}
+
+/// Writes a temporary file next to `path` and renames it into place, so that an
+/// interrupted write never leaves a partial file behind.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+    let temp_path = with_suffix(path, ".tmp");
+    let mut file = File::create(&temp_path)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    fs::rename(&temp_path, path)?;
+    Ok(())
+}
+
+/// Caches a downloaded file along with its metadata.
+pub fn store(path: &Path, contents: &[u8]) -> Result<()> {
+    let downloaded = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    store_as_of(path, contents, downloaded)
+}
+
+fn store_as_of(path: &Path, contents: &[u8], downloaded: u64) -> Result<()> {
+    write_atomic(path, contents)?;
+    let metadata = Metadata::of(contents, downloaded);
+    write_atomic(&metadata_path(path), (metadata.to_line() + "\n").as_bytes())
+}
+
+/// Deletes a cached file along with its metadata, returning how many files were
+/// there.
+pub fn remove(path: &Path) -> Result<usize> {
+    let mut removed = 0;
+    for path in [path.to_path_buf(), metadata_path(path)] {
+        if path.exists() {
+            fs::remove_file(path)?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+pub fn read_metadata(path: &Path) -> Result<Option<Metadata>> {
+    let metadata_path = metadata_path(path);
+    if !metadata_path.exists() {
+        return Ok(None);
+    }
+    Metadata::from_line(&fs::read_to_string(metadata_path)?).map(Some)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Check {
+    Intact,
+    /// Cached before metadata was recorded, or copied in by hand
+    NoMetadata,
+    Mismatch(String),
+}
+
+/// Compares a cached file against the metadata recorded when it was downloaded.
+pub fn check(path: &Path) -> Result<Check> {
+    let Some(metadata) = read_metadata(path)? else {
+        return Ok(Check::NoMetadata);
+    };
+    let contents = fs::read(path)?;
+    if contents.len() as u64 != metadata.size {
+        return Ok(Check::Mismatch(format!(
+            "is {} bytes but {} were downloaded",
+            contents.len(),
+            metadata.size
+        )));
+    }
+    if sha256(&contents) != metadata.sha256 {
+        return Ok(Check::Mismatch(
+            "doesn't match the checksum of what was downloaded".to_string(),
+        ));
+    }
+    Ok(Check::Intact)
+}
+
+/// Key for the encrypted input cache.
+#[derive(Clone)]
+pub struct CacheKey(Key);
+
+// Keeps the key out of debug output
+impl Debug for CacheKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "CacheKey(..)")
+    }
+}
+
+/// Parses a key given as 64 hex digits, such as the output of
+/// `openssl rand -hex 32`.
+pub fn parse_cache_key(s: &str) -> Result<CacheKey> {
+    let s = s.trim();
+    let invalid =
+        || anyhow!("The cache key must be 64 hex digits, generate one with `openssl rand -hex 32`");
+    if s.len() != 64 || !s.is_ascii() {
+        return Err(invalid());
+    }
+    let bytes = (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| invalid())?;
+    Ok(CacheKey(*Key::from_slice(&bytes)))
+}
+
+/// Marks encrypted files, in case the format ever has to change.
+const MAGIC: &[u8] = b"aoc-encrypted-v1\n";
+const NONCE_SIZE: usize = 12;
+
+pub fn encrypted_path(path: &Path) -> PathBuf {
+    with_suffix(path, ".enc")
+}
+
+pub fn encrypt(key: &CacheKey, plaintext: &[u8]) -> Result<Vec<u8>> {
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = ChaCha20Poly1305::new(&key.0)
+        .encrypt(&nonce, plaintext)
+        .map_err(|_| anyhow!("Can't encrypt the input"))?;
+    Ok([MAGIC, nonce.as_slice(), &ciphertext].concat())
+}
+
+pub fn decrypt(key: &CacheKey, data: &[u8]) -> Result<Vec<u8>> {
+    let data = data
+        .strip_prefix(MAGIC)
+        .ok_or_else(|| anyhow!("Not an encrypted input"))?;
+    ensure!(data.len() >= NONCE_SIZE, "The encrypted input is truncated");
+    let (nonce, ciphertext) = data.split_at(NONCE_SIZE);
+    ChaCha20Poly1305::new(&key.0)
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| anyhow!("Can't decrypt the input, the key is wrong or the file is damaged"))
+}
+
+/// A cached input of any year.
+struct Entry {
+    year: Year,
+    day: Day,
+    path: PathBuf,
+    encrypted: bool,
+}
+
+/// Every cached input under `inputs_dir`, by year and day.
+fn entries(inputs_dir: &Path) -> Result<Vec<Entry>> {
+    if !inputs_dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut entries = Vec::new();
+    for year_dir in fs::read_dir(inputs_dir)? {
+        let year_dir = year_dir?.path();
+        let Some(Ok(year)) = year_dir
+            .file_name()
+            .map(|name| parse_year(&name.to_string_lossy()))
+        else {
+            continue;
+        };
+        for day in year.days() {
+            let path = year_dir.join(format!("day-{}-input.txt", day.day_number()));
+            for (path, encrypted) in [(encrypted_path(&path), true), (path, false)] {
+                if path.exists() {
+                    entries.push(Entry {
+                        year,
+                        day,
+                        path,
+                        encrypted,
+                    });
+                }
+            }
+        }
+    }
+    entries.sort_by_key(|entry| (entry.year.year_number(), entry.day.day_number()));
+    Ok(entries)
+}
+
+fn format_size(bytes: u64) -> String {
+    if bytes < 1024 {
+        format!("{bytes} B")
+    } else {
+        format!("{:.1} KiB", bytes as f64 / 1024.0)
+    }
+}
+
+pub async fn cache(runner: &Runner, command: CacheCommand, format: Format) -> Result<()> {
+    match command {
+        CacheCommand::List => list(runner, format),
+        CacheCommand::Verify => verify(runner, format),
+        CacheCommand::Clear(opts) => clear(runner, opts),
+        CacheCommand::Prefetch(opts) => prefetch(runner, opts).await,
+        CacheCommand::Encrypt => encrypt_all(runner),
+        CacheCommand::Decrypt => decrypt_all(runner),
+    }
+}
+
+fn list(runner: &Runner, format: Format) -> Result<()> {
+    let mut rows = Vec::new();
+    for entry in entries(runner.inputs_dir())? {
+        let downloaded = read_metadata(&entry.path)?
+            .and_then(|metadata| Utc.timestamp_opt(metadata.downloaded as i64, 0).single())
+            .map_or("-".to_string(), |time| {
+                time.format("%Y-%m-%d %H:%M UTC").to_string()
+            });
+        rows.push(vec![
+            entry.year.year_number().to_string(),
+            entry.day.day_number().to_string(),
+            format_size(fs::metadata(&entry.path)?.len()),
+            downloaded,
+            if entry.encrypted { "yes" } else { "no" }.to_string(),
+        ]);
+    }
+    if rows.is_empty() {
+        println!("Nothing cached in {}", runner.inputs_dir().display());
+        return Ok(());
+    }
+    println!(
+        "{}",
+        table(
+            format,
+            &["Year", "Day", "Size", "Downloaded", "Encrypted"],
+            &rows
+        )
+    );
+    Ok(())
+}
+
+fn verify(runner: &Runner, format: Format) -> Result<()> {
+    let mut rows = Vec::new();
+    let mut problems = 0;
+    for entry in entries(runner.inputs_dir())? {
+        let contents = fs::read(&entry.path)?;
+        let contents = match (entry.encrypted, runner.cache_key()) {
+            (false, _) => Ok(contents),
+            (true, Ok(key)) => decrypt(key, &contents),
+            (true, Err(_)) => Err(anyhow!("can't be decrypted without the cache key")),
+        };
+        let status = match (check(&entry.path)?, contents) {
+            (Check::Mismatch(problem), _) => format!("CORRUPT: {problem}"),
+            (_, Err(error)) => format!("UNREADABLE: {error}"),
+            (check, Ok(contents)) => match (check, check_body(&String::from_utf8_lossy(&contents)))
+            {
+                (_, Err(InputError::SessionExpired)) => {
+                    "INVALID: a login page or message".to_string()
+                }
+                (_, Err(_)) => "INVALID: empty".to_string(),
+                (Check::NoMetadata, Ok(())) => "ok, no checksum recorded".to_string(),
+                (_, Ok(())) => "ok".to_string(),
+            },
+        };
+        if !status.starts_with("ok") {
+            problems += 1;
+        }
+        rows.push(vec![
+            entry.year.year_number().to_string(),
+            entry.day.day_number().to_string(),
+            status,
+        ]);
+    }
+
+    println!("{}", table(format, &["Year", "Day", "Status"], &rows));
+    if problems > 0 {
+        bail!("{problems} cached inputs are broken, clear them with `cache clear --year YEAR --day N` and download them again");
+    }
+    Ok(())
+}
+
+/// Deletes cached inputs and puzzle pages. Examples, answers and submissions stay,
+/// since they can't all be downloaded again.
+fn clear(runner: &Runner, opts: ClearOpts) -> Result<()> {
+    let days = match opts.day {
+        Some(day) => vec![day],
+        None => runner.year().days().collect(),
+    };
+    let mut removed = 0;
+    for day in days {
+        let input = runner.input_path(day);
+        removed += remove(&encrypted_path(&input))?;
+        removed += remove(&input)?;
+        removed += remove(&runner.puzzle_path(day))?;
+    }
+    println!(
+        "Removed {removed} files from {}",
+        runner.cache_dir().display()
+    );
+    Ok(())
+}
+
+async fn prefetch(runner: &Runner, opts: PrefetchOpts) -> Result<()> {
+    let unlocked = runner.year().unlocked_days(Utc::now()).collect::<Vec<_>>();
+    let days = match opts.day {
+        Some(day) if !unlocked.contains(&day) => {
+            bail!("Day {} isn't unlocked yet", day.day_number())
+        }
+        Some(day) => vec![day],
+        None => unlocked,
+    };
+
+    let mut downloaded = 0;
+    for day in days {
+        let path = runner.input_path(day);
+        if path.exists() || encrypted_path(&path).exists() {
+            continue;
+        }
+        if downloaded > 0 {
+            sleep(Duration::from_secs(opts.delay)).await;
+        }
+        runner.get_input(day, false).await?;
+        downloaded += 1;
+    }
+    println!("Downloaded {downloaded} inputs");
+    Ok(())
+}
+
+/// Replaces every plaintext input with an encrypted one, keeping the time it was
+/// downloaded.
+fn encrypt_all(runner: &Runner) -> Result<()> {
+    let key = runner.cache_key()?;
+    let mut encrypted = 0;
+    for entry in entries(runner.inputs_dir())? {
+        if entry.encrypted {
+            continue;
+        }
+        let downloaded = downloaded(&entry.path)?;
+        let ciphertext = encrypt(key, &fs::read(&entry.path)?)?;
+        store_as_of(&encrypted_path(&entry.path), &ciphertext, downloaded)?;
+        remove(&entry.path)?;
+        encrypted += 1;
+    }
+    println!("Encrypted {encrypted} inputs");
+    Ok(())
+}
+
+/// Replaces every encrypted input with a plaintext one, keeping the time it was
+/// downloaded.
+fn decrypt_all(runner: &Runner) -> Result<()> {
+    let key = runner.cache_key()?;
+    let mut decrypted = 0;
+    for entry in entries(runner.inputs_dir())? {
+        if !entry.encrypted {
+            continue;
+        }
+        let downloaded = downloaded(&entry.path)?;
+        let plaintext = decrypt(key, &fs::read(&entry.path)?)?;
+        store_as_of(&entry.path.with_extension(""), &plaintext, downloaded)?;
+        remove(&entry.path)?;
+        decrypted += 1;
+    }
+    println!("Decrypted {decrypted} inputs");
+    Ok(())
+}
+
+/// When a cached file was downloaded, or now when that wasn't recorded.
+fn downloaded(path: &Path) -> Result<u64> {
+    match read_metadata(path)? {
+        Some(metadata) => Ok(metadata.downloaded),
+        None => Ok(SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::process;
+
+    #[test]
+    fn detects_changes() {
+        let dir = env::temp_dir().join(format!("aoc-cache-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("day-1-input.txt");
+
+        fs::write(&path, "1000\n").unwrap();
+        assert_eq!(check(&path).unwrap(), Check::NoMetadata);
+
+        store(&path, b"1000\n2000\n").unwrap();
+        assert_eq!(check(&path).unwrap(), Check::Intact);
+        assert!(!with_suffix(&path, ".tmp").exists());
+        let metadata = read_metadata(&path).unwrap().unwrap();
+        assert_eq!(metadata.size, 10);
+        assert_eq!(
+            metadata.sha256,
+            "c034ee159dbab9f30d8fa3488b21e19593a8fe2b21c813cb0f40b08532350906"
+        );
+
+        fs::write(&path, "1000\n").unwrap();
+        assert!(matches!(check(&path).unwrap(), Check::Mismatch(_)));
+        fs::write(&path, "1000\n2001\n").unwrap();
+        assert!(matches!(check(&path).unwrap(), Check::Mismatch(_)));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn finds_entries() {
+        let dir = env::temp_dir().join(format!("aoc-cache-entries-{}", process::id()));
+        for path in [
+            "2022/day-1-input.txt",
+            "2022/day-12-input.txt",
+            "2022/day-1-puzzle.html",
+            "2021/day-3-input.txt",
+            "examples/day-1-input.txt",
+        ] {
+            let path = dir.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "1\n").unwrap();
+        }
+
+        let found = entries(&dir)
+            .unwrap()
+            .iter()
+            .map(|entry| (entry.year.year_number(), entry.day.day_number()))
+            .collect::<Vec<_>>();
+        assert_eq!(found, [(2021, 3), (2022, 1), (2022, 12)]);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    const KEY: &str = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+
+    #[test]
+    fn encryption_round_trip() {
+        let key = parse_cache_key(KEY).unwrap();
+        let encrypted = encrypt(&key, b"1000\n2000\n").unwrap();
+        assert!(encrypted.starts_with(MAGIC));
+        assert!(!encrypted.windows(4).any(|window| window == b"1000"));
+        assert_eq!(decrypt(&key, &encrypted).unwrap(), b"1000\n2000\n");
+
+        let other_key = parse_cache_key(&KEY.replace("1f", "ff")).unwrap();
+        assert!(decrypt(&other_key, &encrypted).is_err());
+        assert!(decrypt(&key, &encrypted[..encrypted.len() - 1]).is_err());
+        assert!(decrypt(&key, b"1000\n").is_err());
+    }
+
+    #[test]
+    fn rejects_bad_keys() {
+        assert!(parse_cache_key("").is_err());
+        assert!(parse_cache_key(&KEY[2..]).is_err());
+        assert!(parse_cache_key(&KEY.replace('0', "g")).is_err());
+        assert!(parse_cache_key(&format!("{KEY}\n")).is_ok());
+    }
+}
@@ -0,0 +1,322 @@
+use crate::cache::{parse_cache_key, CacheKey};
+use crate::runner::{parse_year, Opts, Year};
+use crate::table::{table, Format};
+use anyhow::{anyhow, Context, Result};
+use reqwest::Url;
+use serde::Deserialize;
+use std::env;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const CONFIG_FILE: &str = "aoc.toml";
+const DEFAULT_BASE_URL: &str = "https://adventofcode.com/";
+
+/// The contents of an `aoc.toml` file, every setting optional.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigFile {
+    session: Option<String>,
+    cache_key: Option<String>,
+    year: Option<u16>,
+    cache_dir: Option<String>,
+    base_url: Option<String>,
+    format: Option<String>,
+}
+
+impl ConfigFile {
+    fn parse(text: &str) -> Result<Self> {
+        Ok(toml::from_str(text)?)
+    }
+
+    fn load(path: &Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let text = fs::read_to_string(path)?;
+        Self::parse(&text)
+            .map(Some)
+            .with_context(|| format!("Invalid config file {}", path.display()))
+    }
+}
+
+/// The user-level config file, under the XDG config directory.
+fn user_config_path() -> Option<PathBuf> {
+    let config_dir = match env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(env::var_os("HOME")?).join(".config"),
+    };
+    Some(config_dir.join("aoc").join(CONFIG_FILE))
+}
+
+/// Where a setting got its value from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Source {
+    CommandLine,
+    Env(&'static str),
+    File(PathBuf),
+    Default,
+}
+
+impl Display for Source {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Source::CommandLine => write!(f, "command line"),
+            Source::Env(name) => write!(f, "{name} environment variable"),
+            Source::File(path) => write!(f, "{}", path.display()),
+            Source::Default => write!(f, "default"),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Setting<T> {
+    pub value: T,
+    pub source: Source,
+}
+
+/// Looks up environment variables, so tests can stand in for the real ones.
+type Env<'a> = &'a dyn Fn(&str) -> Option<String>;
+
+/// Looks a setting up on the command line, then in the environment, then in the
+/// config files in the order given.
+fn resolve<T>(
+    cli: Option<T>,
+    env: Env,
+    env_var: &'static str,
+    files: &[(PathBuf, ConfigFile)],
+    in_file: impl Fn(&ConfigFile) -> Option<String>,
+    parse: impl Fn(&str) -> Result<T>,
+) -> Result<Option<Setting<T>>> {
+    if let Some(value) = cli {
+        return Ok(Some(Setting {
+            value,
+            source: Source::CommandLine,
+        }));
+    }
+    // Empty variables count as unset, so a setting can be cleared for one command
+    if let Some(value) = env(env_var).filter(|value| !value.is_empty()) {
+        let value = parse(&value).with_context(|| format!("Invalid {env_var}"))?;
+        return Ok(Some(Setting {
+            value,
+            source: Source::Env(env_var),
+        }));
+    }
+    for (path, file) in files {
+        if let Some(value) = in_file(file) {
+            let value = parse(&value).with_context(|| format!("Invalid {}", path.display()))?;
+            return Ok(Some(Setting {
+                value,
+                source: Source::File(path.clone()),
+            }));
+        }
+    }
+    Ok(None)
+}
+
+fn or_default<T>(setting: Option<Setting<T>>, default: T) -> Setting<T> {
+    setting.unwrap_or(Setting {
+        value: default,
+        source: Source::Default,
+    })
+}
+
+/// The effective settings. Each comes from the command line, the environment,
+/// `aoc.toml` in the working directory or the user's `aoc.toml`, whichever has it
+/// first.
+pub struct Config {
+    pub session: Option<Setting<String>>,
+    pub cache_key: Option<Setting<CacheKey>>,
+    pub year: Setting<Year>,
+    pub cache_dir: Setting<PathBuf>,
+    pub base_url: Setting<Url>,
+    pub format: Setting<Format>,
+}
+
+impl Config {
+    pub fn load(opts: &Opts) -> Result<Self> {
+        let mut paths = vec![PathBuf::from(CONFIG_FILE)];
+        paths.extend(user_config_path());
+        let mut files = Vec::new();
+        for path in paths {
+            if let Some(file) = ConfigFile::load(&path)? {
+                files.push((path, file));
+            }
+        }
+        Self::resolve(opts, &|name| env::var(name).ok(), &files)
+    }
+
+    fn resolve(opts: &Opts, env: Env, files: &[(PathBuf, ConfigFile)]) -> Result<Self> {
+        let session = resolve(
+            None,
+            env,
+            "SESSION_COOKIE",
+            files,
+            |file| file.session.clone(),
+            |session| Ok(session.to_string()),
+        )?;
+        let cache_key = resolve(
+            None,
+            env,
+            "AOC_CACHE_KEY",
+            files,
+            |file| file.cache_key.clone(),
+            parse_cache_key,
+        )?;
+        let year = resolve(
+            opts.year,
+            env,
+            "AOC_YEAR",
+            files,
+            |file| file.year.map(|year| year.to_string()),
+            |year| parse_year(year).map_err(|e| anyhow!(e)),
+        )?;
+        let cache_dir = resolve(
+            opts.cache_dir.clone(),
+            env,
+            "AOC_CACHE_DIR",
+            files,
+            |file| file.cache_dir.clone(),
+            |dir| Ok(PathBuf::from(dir)),
+        )?;
+        let base_url = resolve(
+            opts.base_url.clone(),
+            env,
+            "AOC_BASE_URL",
+            files,
+            |file| file.base_url.clone(),
+            parse_base_url,
+        )?;
+        let format = resolve(
+            opts.format,
+            env,
+            "AOC_FORMAT",
+            files,
+            |file| file.format.clone(),
+            |format| format.parse().map_err(|e: &str| anyhow!(e)),
+        )?;
+
+        Ok(Self {
+            session,
+            cache_key,
+            year: or_default(year, parse_year("2022").unwrap()),
+            cache_dir: or_default(cache_dir, PathBuf::from("inputs")),
+            base_url: or_default(base_url, parse_base_url(DEFAULT_BASE_URL)?),
+            format: or_default(format, Format::Markdown),
+        })
+    }
+
+    /// Prints every effective setting along with where it came from.
+    pub fn show(&self) {
+        let row = |name: &str, value: String, source: &Source| {
+            vec![name.to_string(), value, source.to_string()]
+        };
+        // Secrets are only shown to be set, never printed
+        let secret = |name: &str, source: Option<&Source>| match source {
+            Some(source) => row(name, "(set)".to_string(), source),
+            None => vec![name.to_string(), "(not set)".to_string(), "-".to_string()],
+        };
+        let rows = vec![
+            secret(
+                "session",
+                self.session.as_ref().map(|session| &session.source),
+            ),
+            secret("cache_key", self.cache_key.as_ref().map(|key| &key.source)),
+            row(
+                "year",
+                self.year.value.year_number().to_string(),
+                &self.year.source,
+            ),
+            row(
+                "cache_dir",
+                self.cache_dir.value.display().to_string(),
+                &self.cache_dir.source,
+            ),
+            row(
+                "base_url",
+                self.base_url.value.to_string(),
+                &self.base_url.source,
+            ),
+            row(
+                "format",
+                self.format.value.name().to_string(),
+                &self.format.source,
+            ),
+        ];
+        println!(
+            "{}",
+            table(self.format.value, &["Setting", "Value", "Source"], &rows)
+        );
+    }
+}
+
+pub fn parse_base_url(s: &str) -> Result<Url> {
+    // Without the trailing slash, joining a path would replace the last segment
+    let with_slash = if s.ends_with('/') {
+        s.to_string()
+    } else {
+        format!("{s}/")
+    };
+    Url::parse(&with_slash).with_context(|| format!("Invalid base URL {s}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base_url() {
+        let config = ConfigFile::parse("base_url = \"http://localhost:8080/aoc\"").unwrap();
+        let base_url = parse_base_url(config.base_url.as_deref().unwrap()).unwrap();
+        assert_eq!(
+            base_url.join("2022/day/1/input").unwrap().as_str(),
+            "http://localhost:8080/aoc/2022/day/1/input"
+        );
+        assert!(ConfigFile::parse("").unwrap().base_url.is_none());
+        assert!(parse_base_url("not a url").is_err());
+    }
+
+    #[test]
+    fn precedence() {
+        let project = ConfigFile::parse("year = 2021\ncache_dir = \"project\"").unwrap();
+        let user = ConfigFile::parse("year = 2020\nformat = \"tsv\"\nsession = \"abc\"").unwrap();
+        let files = vec![
+            (PathBuf::from("aoc.toml"), project),
+            (PathBuf::from("user/aoc.toml"), user),
+        ];
+
+        let no_env = |_: &str| None;
+        let opts = Opts::try_parse_args(["aoc", "--year", "2019", "run-all"]).unwrap();
+        let config = Config::resolve(&opts, &no_env, &files).unwrap();
+        assert_eq!(config.year.value, parse_year("2019").unwrap());
+        assert_eq!(config.year.source, Source::CommandLine);
+
+        let opts = Opts::try_parse_args(["aoc", "run-all"]).unwrap();
+        let config = Config::resolve(&opts, &no_env, &files).unwrap();
+        assert_eq!(config.year.value, parse_year("2021").unwrap());
+        assert_eq!(config.year.source, Source::File(PathBuf::from("aoc.toml")));
+        assert_eq!(config.cache_dir.value, PathBuf::from("project"));
+        assert_eq!(config.format.value, Format::Tsv);
+        assert_eq!(
+            config.format.source,
+            Source::File(PathBuf::from("user/aoc.toml"))
+        );
+        assert_eq!(config.base_url.source, Source::Default);
+
+        let env = |name: &str| match name {
+            "AOC_YEAR" => Some("2018".to_string()),
+            "AOC_FORMAT" => Some(String::new()),
+            _ => None,
+        };
+        let config = Config::resolve(&opts, &env, &files).unwrap();
+        assert_eq!(config.year.value, parse_year("2018").unwrap());
+        assert_eq!(config.year.source, Source::Env("AOC_YEAR"));
+        assert_eq!(config.format.value, Format::Tsv);
+    }
+
+    #[test]
+    fn rejects_unknown_settings() {
+        assert!(ConfigFile::parse("sesion = \"abc\"").is_err());
+        assert!(ConfigFile::parse("year = \"twenty\"").is_err());
+    }
+}
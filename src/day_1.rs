@@ -1,29 +1,37 @@
+use crate::solution::Solution;
 use crate::Lines;
 
-fn common(lines: Lines) -> Vec<i32> {
-    let mut elves = Vec::new();
-    let mut elf_sum = 0;
-    for line in lines {
-        if let Ok(num) = line.parse::<i32>() {
-            elf_sum += num;
-        } else {
-            elves.push(elf_sum);
-            elf_sum = 0;
+pub struct Day1;
+
+impl Solution for Day1 {
+    // Calories carried by each elf, sorted from the most to the least
+    type Input = Vec<i32>;
+    type Output1 = i32;
+    type Output2 = i32;
+
+    fn parse(lines: Lines) -> anyhow::Result<Vec<i32>> {
+        let mut elves = Vec::new();
+        let mut elf_sum = 0;
+        for line in lines {
+            if let Ok(num) = line.parse::<i32>() {
+                elf_sum += num;
+            } else {
+                elves.push(elf_sum);
+                elf_sum = 0;
+            }
         }
-    }
-    elves.push(elf_sum);
+        elves.push(elf_sum);
 
-    elves.sort();
-    elves.reverse();
-    elves
-}
+        elves.sort();
+        elves.reverse();
+        Ok(elves)
+    }
 
-pub fn day_1_1(lines: Lines) -> anyhow::Result<i32> {
-    let elves = common(lines);
-    Ok(elves.iter().take(1).sum())
-}
+    fn part_1(elves: &Vec<i32>) -> anyhow::Result<i32> {
+        Ok(elves.iter().take(1).sum())
+    }
 
-pub fn day_1_2(lines: Lines) -> anyhow::Result<i32> {
-    let elves = common(lines);
-    Ok(elves.iter().take(3).sum())
+    fn part_2(elves: &Vec<i32>) -> anyhow::Result<i32> {
+        Ok(elves.iter().take(3).sum())
+    }
 }
@@ -1,3 +1,4 @@
+use crate::solution::Solution;
 use crate::Lines;
 use anyhow::{anyhow, Result};
 use std::str::FromStr;
@@ -48,7 +49,7 @@ impl Shape {
     }
 }
 
-struct Round {
+pub struct Round {
     opponent_shape: Shape,
     player_shape: Shape,
 }
@@ -119,7 +120,7 @@ impl FromStr for Outcome {
     }
 }
 
-struct RoundWithOutcome {
+pub struct RoundWithOutcome {
     opponent_shape: Shape,
     outcome: Outcome,
 }
@@ -150,23 +151,33 @@ impl RoundWithOutcome {
     }
 }
 
-pub fn day_2_1(input: Lines) -> Result<i32> {
-    let rounds = input
-        .map(|line| line.parse())
-        .collect::<Result<Vec<Round>, _>>()?;
-    Ok(rounds.iter().map(|round| round.score()).sum())
-}
+pub struct Day2;
+
+impl Solution for Day2 {
+    // Each line read both as a pair of shapes and as a shape with the expected outcome
+    type Input = Vec<(Round, RoundWithOutcome)>;
+    type Output1 = i32;
+    type Output2 = i32;
+
+    fn parse(lines: Lines) -> Result<Self::Input> {
+        lines
+            .map(|line| Ok((line.parse()?, line.parse()?)))
+            .collect()
+    }
 
-pub fn day_2_2(input: Lines) -> Result<i32> {
-    let rounds = input
-        .map(|line| line.parse())
-        .collect::<Result<Vec<RoundWithOutcome>, _>>()?;
-    Ok(rounds.iter().map(|round| round.score()).sum())
+    fn part_1(rounds: &Self::Input) -> Result<i32> {
+        Ok(rounds.iter().map(|(round, _)| round.score()).sum())
+    }
+
+    fn part_2(rounds: &Self::Input) -> Result<i32> {
+        Ok(rounds.iter().map(|(_, round)| round.score()).sum())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::runner::Part;
     use crate::tests::aoc_test;
 
     const INPUT: &str = "A Y
@@ -175,21 +186,21 @@ C Z";
 
     #[test]
     fn day_2_1_test() {
-        aoc_test(INPUT, day_2_1, 15);
+        aoc_test::<Day2>(INPUT, Part::One, 15);
     }
 
     #[test]
     fn day_2_2_test() {
-        aoc_test(INPUT, day_2_2, 12);
+        aoc_test::<Day2>(INPUT, Part::Two, 12);
     }
 
     #[test]
     fn day_2_2_test_2() {
-        aoc_test(
+        aoc_test::<Day2>(
             "A Z
 C X
 B Y",
-            day_2_2,
+            Part::Two,
             15,
         );
     }
@@ -1,3 +1,4 @@
+use crate::solution::Solution;
 use crate::Lines;
 use anyhow::bail;
 use itertools::Itertools;
@@ -15,57 +16,69 @@ fn priority(c: char) -> i32 {
     }
 }
 
-pub fn day_3_1(lines: Lines) -> Result<i32, anyhow::Error> {
-    let mut sum = 0;
-    for line in lines {
-        if line.len() % 2 != 0 {
-            bail!("Invalid line length: {}", line.len());
-        }
-        let first_half = &line[0..line.len() / 2].chars().collect::<BTreeSet<_>>();
-        let second_half = &line[line.len() / 2..].chars().collect::<BTreeSet<_>>();
-
-        let in_both = first_half
-            .intersection(second_half)
-            .collect::<Vec<_>>()
-            .first()
-            .copied()
-            .copied();
-
-        if let Some(c) = in_both {
-            sum += priority(c);
-        } else {
-            bail!("No common character in line: {}", line);
-        }
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Input = Vec<String>;
+    type Output1 = i32;
+    type Output2 = i32;
+
+    fn parse(lines: Lines) -> Result<Vec<String>, anyhow::Error> {
+        Ok(lines.collect())
     }
 
-    Ok(sum)
-}
+    fn part_1(rucksacks: &Vec<String>) -> Result<i32, anyhow::Error> {
+        let mut sum = 0;
+        for line in rucksacks {
+            if line.len() % 2 != 0 {
+                bail!("Invalid line length: {}", line.len());
+            }
+            let first_half = &line[0..line.len() / 2].chars().collect::<BTreeSet<_>>();
+            let second_half = &line[line.len() / 2..].chars().collect::<BTreeSet<_>>();
 
-pub fn day_3_2(lines: Lines) -> Result<i32, anyhow::Error> {
-    let mut sum = 0;
-    for (elf1, elf2, elf3) in lines.tuples() {
-        let set1 = elf1.chars().collect::<BTreeSet<_>>();
-        let set2 = elf2.chars().collect::<BTreeSet<_>>();
-        let set3 = elf3.chars().collect::<BTreeSet<_>>();
-
-        let in_all = set1
-            .intersection(&set2)
-            .cloned()
-            .collect::<BTreeSet<_>>()
-            .intersection(&set3)
-            .collect::<Vec<_>>()
-            .first()
-            .copied()
-            .copied();
-
-        if let Some(c) = in_all {
-            sum += priority(c);
-        } else {
-            bail!("No common character in line: {}, {}, {}", elf1, elf2, elf3);
+            let in_both = first_half
+                .intersection(second_half)
+                .collect::<Vec<_>>()
+                .first()
+                .copied()
+                .copied();
+
+            if let Some(c) = in_both {
+                sum += priority(c);
+            } else {
+                bail!("No common character in line: {}", line);
+            }
         }
+
+        Ok(sum)
     }
 
-    Ok(sum)
+    fn part_2(rucksacks: &Vec<String>) -> Result<i32, anyhow::Error> {
+        let mut sum = 0;
+        for (elf1, elf2, elf3) in rucksacks.iter().tuples() {
+            let set1 = elf1.chars().collect::<BTreeSet<_>>();
+            let set2 = elf2.chars().collect::<BTreeSet<_>>();
+            let set3 = elf3.chars().collect::<BTreeSet<_>>();
+
+            let in_all = set1
+                .intersection(&set2)
+                .cloned()
+                .collect::<BTreeSet<_>>()
+                .intersection(&set3)
+                .collect::<Vec<_>>()
+                .first()
+                .copied()
+                .copied();
+
+            if let Some(c) = in_all {
+                sum += priority(c);
+            } else {
+                bail!("No common character in line: {}, {}, {}", elf1, elf2, elf3);
+            }
+        }
+
+        Ok(sum)
+    }
 }
 
 #[cfg(test)]
@@ -90,13 +103,13 @@ CrZsJsPPZsGzwwsLwLmpwMDw";
 
     #[test]
     fn test_day_3_1() {
-        let input = Lines::from_text(INPUT);
-        assert_eq!(day_3_1(input).unwrap(), 157);
+        let input = Day3::parse(Lines::from_string(INPUT)).unwrap();
+        assert_eq!(Day3::part_1(&input).unwrap(), 157);
     }
 
     #[test]
     fn test_day_3_2() {
-        let input = Lines::from_text(INPUT);
-        assert_eq!(day_3_2(input).unwrap(), 70);
+        let input = Day3::parse(Lines::from_string(INPUT)).unwrap();
+        assert_eq!(Day3::part_2(&input).unwrap(), 70);
     }
 }
@@ -1,50 +1,66 @@
+use crate::solution::Solution;
 use crate::Lines;
 use anyhow::Result;
 use regex::Regex;
 
-fn common(lines: Lines, predicate: impl Fn(i32, i32, i32, i32) -> bool) -> Result<i32> {
-    let regex = Regex::new(r"(\d+)-(\d+),(\d+)-(\d+)")?;
+type Assignment = (i32, i32, i32, i32);
 
-    let mut count = 0;
-    for line in lines {
-        let captures = regex
-            .captures(&line)
-            .ok_or_else(|| anyhow::anyhow!("Invalid line: {}", line))?;
-        let (x1, x2, y1, y2) = (
-            captures[1].parse::<i32>()?,
-            captures[2].parse::<i32>()?,
-            captures[3].parse::<i32>()?,
-            captures[4].parse::<i32>()?,
-        );
-        if predicate(x1, x2, y1, y2) {
-            count += 1;
+fn count(assignments: &[Assignment], predicate: impl Fn(i32, i32, i32, i32) -> bool) -> i32 {
+    assignments
+        .iter()
+        .filter(|&&(x1, x2, y1, y2)| predicate(x1, x2, y1, y2))
+        .count() as i32
+}
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Input = Vec<Assignment>;
+    type Output1 = i32;
+    type Output2 = i32;
+
+    fn parse(lines: Lines) -> Result<Vec<Assignment>> {
+        let regex = Regex::new(r"(\d+)-(\d+),(\d+)-(\d+)")?;
+
+        let mut assignments = Vec::new();
+        for line in lines {
+            let captures = regex
+                .captures(&line)
+                .ok_or_else(|| anyhow::anyhow!("Invalid line: {}", line))?;
+            assignments.push((
+                captures[1].parse::<i32>()?,
+                captures[2].parse::<i32>()?,
+                captures[3].parse::<i32>()?,
+                captures[4].parse::<i32>()?,
+            ));
         }
+        Ok(assignments)
     }
-    Ok(count)
-}
 
-pub fn day_4_1(lines: Lines) -> anyhow::Result<i32> {
-    // check if one is contained in the other
-    common(lines, |x1, x2, y1, y2| {
-        (x1 <= y1 && y2 <= x2) || (y1 <= x1 && x2 <= y2)
-    })
-}
+    fn part_1(assignments: &Vec<Assignment>) -> anyhow::Result<i32> {
+        // check if one is contained in the other
+        Ok(count(assignments, |x1, x2, y1, y2| {
+            (x1 <= y1 && y2 <= x2) || (y1 <= x1 && x2 <= y2)
+        }))
+    }
 
-pub fn day_4_2(lines: Lines) -> anyhow::Result<i32> {
-    // check if there is *any* overlap
-    let predicate = |x1, x2, y1, y2| {
-        (x1 <= y1 && y1 <= x2)
-            || (y1 <= x1 && x1 <= y2)
-            || (x1 <= y2 && y2 <= x2)
-            || (y1 <= x2 && x2 <= y2)
-            || (x1 == y1 && x2 == y2)
-    };
-    common(lines, predicate)
+    fn part_2(assignments: &Vec<Assignment>) -> anyhow::Result<i32> {
+        // check if there is *any* overlap
+        let predicate = |x1, x2, y1, y2| {
+            (x1 <= y1 && y1 <= x2)
+                || (y1 <= x1 && x1 <= y2)
+                || (x1 <= y2 && y2 <= x2)
+                || (y1 <= x2 && x2 <= y2)
+                || (x1 == y1 && x2 == y2)
+        };
+        Ok(count(assignments, predicate))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::runner::Part;
     use crate::tests::aoc_test;
 
     const INPUT: &str = "2-4,6-8
@@ -56,11 +72,11 @@ mod tests {
 
     #[test]
     fn day_4_1_test() {
-        aoc_test(INPUT, day_4_1, 2);
+        aoc_test::<Day4>(INPUT, Part::One, 2);
     }
 
     #[test]
     fn day_4_2_test() {
-        aoc_test(INPUT, day_4_2, 4);
+        aoc_test::<Day4>(INPUT, Part::Two, 4);
     }
 }
@@ -1,10 +1,12 @@
+use crate::solution::Solution;
 use crate::Lines;
 use anyhow::Result;
 use anyhow::{anyhow, bail};
 use once_cell::sync::Lazy;
 use regex::Regex;
 
-struct Stacks(Vec<Vec<Crate>>);
+#[derive(Clone)]
+pub struct Stacks(Vec<Vec<Crate>>);
 
 impl Stacks {
     fn execute_order_9000(&mut self, order: &Order) -> Result<()> {
@@ -61,7 +63,7 @@ impl Stacks {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-struct Crate(char);
+pub struct Crate(char);
 
 struct Parser<'a> {
     index: usize,
@@ -103,7 +105,7 @@ impl<'a> Parser<'a> {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-struct Order {
+pub struct Order {
     how_many: usize,
     from: usize,
     to: usize,
@@ -173,25 +175,38 @@ fn parse(mut lines: Lines) -> Result<(Stacks, Vec<Order>)> {
     Ok((stacks, orders))
 }
 
-pub fn day_5_1(lines: Lines) -> Result<String> {
-    let (mut stacks, orders) = parse(lines)?;
-    for order in orders {
-        stacks.execute_order_9000(&order)?;
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Input = (Stacks, Vec<Order>);
+    type Output1 = String;
+    type Output2 = String;
+
+    fn parse(lines: Lines) -> Result<(Stacks, Vec<Order>)> {
+        parse(lines)
     }
-    Ok(stacks.read_top())
-}
 
-pub fn day_5_2(lines: Lines) -> Result<String> {
-    let (mut stacks, orders) = parse(lines)?;
-    for order in orders {
-        stacks.execute_order_9001(&order)?;
+    fn part_1((stacks, orders): &(Stacks, Vec<Order>)) -> Result<String> {
+        let mut stacks = stacks.clone();
+        for order in orders {
+            stacks.execute_order_9000(order)?;
+        }
+        Ok(stacks.read_top())
+    }
+
+    fn part_2((stacks, orders): &(Stacks, Vec<Order>)) -> Result<String> {
+        let mut stacks = stacks.clone();
+        for order in orders {
+            stacks.execute_order_9001(order)?;
+        }
+        Ok(stacks.read_top())
     }
-    Ok(stacks.read_top())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::runner::Part;
     use crate::tests::aoc_test;
 
     const INPUT: &str = "    [D]
@@ -218,17 +233,17 @@ move 2 from 3 to 1
 
     #[test]
     fn test_day_5_1() {
-        aoc_test(INPUT, day_5_1, "CMZ".to_string());
+        aoc_test::<Day5>(INPUT, Part::One, "CMZ");
     }
 
     #[test]
     fn test_day_5_2() {
-        aoc_test(INPUT, day_5_2, "MCD".to_string());
+        aoc_test::<Day5>(INPUT, Part::Two, "MCD");
     }
 
     #[test]
     fn my_test() {
-        aoc_test(INPUT_2, day_5_1, "ACF".to_string());
+        aoc_test::<Day5>(INPUT_2, Part::One, "ACF");
     }
 
     #[test]
@@ -1,3 +1,4 @@
+use crate::solution::Solution;
 use crate::Lines;
 use anyhow::{anyhow, Result};
 
@@ -17,36 +18,47 @@ fn find_marker(input: &str, window_size: usize) -> Result<usize> {
     Err(anyhow!("No solution found"))
 }
 
-pub fn day_6_1(mut lines: Lines) -> Result<usize> {
-    let input = lines.next().ok_or_else(|| anyhow!("No input"))?;
-    find_marker(&input, 4)
-}
+pub struct Day6;
+
+impl Solution for Day6 {
+    type Input = String;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse(mut lines: Lines) -> Result<String> {
+        lines.next().ok_or_else(|| anyhow!("No input"))
+    }
 
-pub fn day_6_2(mut lines: Lines) -> Result<usize> {
-    let input = lines.next().ok_or_else(|| anyhow!("No input"))?;
-    find_marker(&input, 14)
+    fn part_1(input: &String) -> Result<usize> {
+        find_marker(input, 4)
+    }
+
+    fn part_2(input: &String) -> Result<usize> {
+        find_marker(input, 14)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::runner::Part;
     use crate::tests::aoc_test;
 
     #[test]
     fn day_6_1_test() {
-        aoc_test("mjqjpqmgbljsphdztnvjfqwrcgsmlb", day_6_1, 7);
-        aoc_test("bvwbjplbgvbhsrlpgdmjqwftvncz", day_6_1, 5);
-        aoc_test("nppdvjthqldpwncqszvftbrmjlhg", day_6_1, 6);
-        aoc_test("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", day_6_1, 10);
-        aoc_test("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", day_6_1, 11);
+        aoc_test::<Day6>("mjqjpqmgbljsphdztnvjfqwrcgsmlb", Part::One, 7);
+        aoc_test::<Day6>("bvwbjplbgvbhsrlpgdmjqwftvncz", Part::One, 5);
+        aoc_test::<Day6>("nppdvjthqldpwncqszvftbrmjlhg", Part::One, 6);
+        aoc_test::<Day6>("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", Part::One, 10);
+        aoc_test::<Day6>("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", Part::One, 11);
     }
 
     #[test]
     fn day_6_2_test() {
-        aoc_test("mjqjpqmgbljsphdztnvjfqwrcgsmlb", day_6_2, 19);
-        aoc_test("bvwbjplbgvbhsrlpgdmjqwftvncz", day_6_2, 23);
-        aoc_test("nppdvjthqldpwncqszvftbrmjlhg", day_6_2, 23);
-        aoc_test("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", day_6_2, 29);
-        aoc_test("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", day_6_2, 26);
+        aoc_test::<Day6>("mjqjpqmgbljsphdztnvjfqwrcgsmlb", Part::Two, 19);
+        aoc_test::<Day6>("bvwbjplbgvbhsrlpgdmjqwftvncz", Part::Two, 23);
+        aoc_test::<Day6>("nppdvjthqldpwncqszvftbrmjlhg", Part::Two, 23);
+        aoc_test::<Day6>("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", Part::Two, 29);
+        aoc_test::<Day6>("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", Part::Two, 26);
     }
 }
@@ -0,0 +1,185 @@
+use crate::runner::Part;
+use crate::solution::Parser;
+use crate::Lines;
+use anyhow::{bail, Result};
+use scraper::{Html, Selector};
+use std::fs;
+use std::path::Path;
+
+/// An example input from the puzzle description, along with the answer the
+/// description gives for it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Example {
+    pub name: String,
+    pub part: Part,
+    pub input: String,
+    pub answer: Option<String>,
+}
+
+impl Example {
+    pub fn run(&self, parse: Parser) -> Result<String> {
+        parse(Lines::from_string(&self.input))?.solve(self.part)
+    }
+}
+
+/// Pulls the examples out of a puzzle page. Each part's example is the first
+/// code block of its description, falling back to the previous part's one since
+/// part 2 usually reuses it, and the answer is the last highlighted code.
+pub fn scrape(html: &str) -> Vec<Example> {
+    let document = Html::parse_document(html);
+    let articles = Selector::parse("article.day-desc").unwrap();
+    let blocks = Selector::parse("pre > code").unwrap();
+    let answers = Selector::parse("code > em, em > code").unwrap();
+
+    let mut examples = Vec::new();
+    let mut input = None;
+    for (article, part) in document.select(&articles).zip([Part::One, Part::Two]) {
+        if let Some(block) = article.select(&blocks).next() {
+            input = Some(block.text().collect::<String>());
+        }
+        let Some(input) = input.clone() else {
+            continue;
+        };
+        let answer = article
+            .select(&answers)
+            .last()
+            .map(|answer| answer.text().collect::<String>());
+        examples.push(Example {
+            name: "scraped".to_string(),
+            part,
+            input,
+            answer,
+        });
+    }
+    examples
+}
+
+fn file_stem(example: &Example) -> String {
+    match example.name.as_str() {
+        "scraped" => format!("part-{}", example.part.part_number()),
+        name => format!("part-{}-{name}", example.part.part_number()),
+    }
+}
+
+/// Writes the scraped examples into `dir` as `part-P.txt` with the answer in
+/// `part-P.answer`, keeping any files that are already there so hand-made
+/// corrections survive.
+pub fn save(dir: &Path, examples: &[Example]) -> Result<()> {
+    fs::create_dir_all(dir)?;
+    for example in examples {
+        let stem = file_stem(example);
+        let input_path = dir.join(format!("{stem}.txt"));
+        if input_path.exists() {
+            continue;
+        }
+        fs::write(input_path, &example.input)?;
+        if let Some(answer) = &example.answer {
+            fs::write(dir.join(format!("{stem}.answer")), answer)?;
+        }
+    }
+    Ok(())
+}
+
+/// Loads every `part-P[-name].txt` example from `dir`, scraped or added by hand,
+/// along with its `.answer` file when there is one.
+pub fn load(dir: &Path) -> Result<Vec<Example>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut examples = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_none_or(|extension| extension != "txt") {
+            continue;
+        }
+        let stem = path.file_stem().unwrap().to_string_lossy().to_string();
+        let Some(rest) = stem.strip_prefix("part-") else {
+            continue;
+        };
+        let (part, name) = rest.split_once('-').unwrap_or((rest, "scraped"));
+        let Ok(part) = part.parse() else {
+            continue;
+        };
+        let answer_path = path.with_extension("answer");
+        let answer = if answer_path.exists() {
+            Some(fs::read_to_string(answer_path)?.trim().to_string())
+        } else {
+            None
+        };
+        examples.push(Example {
+            name: name.to_string(),
+            part,
+            input: fs::read_to_string(&path)?,
+            answer,
+        });
+    }
+    examples.sort_by_key(|example| (example.part.part_number(), example.name.clone()));
+    Ok(examples)
+}
+
+/// Runs the examples for `parts`, printing how each went, and fails if any of
+/// them gives a different answer than expected.
+pub fn check(examples: &[Example], parts: &[Part], parse: Parser) -> Result<()> {
+    let mut failures = 0;
+    for example in examples
+        .iter()
+        .filter(|example| parts.contains(&example.part))
+    {
+        let label = format!(
+            "Part {} example ({})",
+            example.part.part_number(),
+            example.name
+        );
+        match (example.run(parse), &example.answer) {
+            (Ok(result), Some(answer)) if result == *answer => {
+                println!("{label}: {result} (correct)")
+            }
+            (Ok(result), Some(answer)) => {
+                failures += 1;
+                println!("{label}: wrong answer");
+                println!("  - expected: {answer}");
+                println!("  + actual:   {result}");
+            }
+            (Ok(result), None) => println!("{label}: {result} (no known answer)"),
+            (Err(error), _) => {
+                failures += 1;
+                println!("{label}: failed: {error:#}");
+            }
+        }
+    }
+
+    if failures > 0 {
+        bail!("{failures} examples failed");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = include_str!("../tests/fixtures/puzzle/day_1.html");
+
+    #[test]
+    fn scraped() {
+        let examples = scrape(PAGE);
+        let input = "1000\n2000\n\n4000\n".to_string();
+        assert_eq!(
+            examples,
+            vec![
+                Example {
+                    name: "scraped".to_string(),
+                    part: Part::One,
+                    input: input.clone(),
+                    answer: Some("4000".to_string()),
+                },
+                Example {
+                    name: "scraped".to_string(),
+                    part: Part::Two,
+                    input,
+                    answer: Some("7000".to_string()),
+                },
+            ]
+        );
+    }
+}
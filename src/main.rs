@@ -1,17 +1,31 @@
-use crate::runner::{Opts, Runner};
-use anyhow::Result;
-use clap::Parser;
-use std::fmt::Display;
+use crate::config::Config;
+use crate::runner::{Command, ConfigCommand, Day, Opts, Part, Parts, RunOpts, Runner};
+use crate::submission::Verdict;
+use anyhow::{bail, Result};
+use std::io::{self, Write};
+use std::process;
+use std::time::{Duration, Instant};
 
 use std::iter::Peekable;
 
+mod answers;
+mod bench;
+mod cache;
+mod config;
 mod day_1;
 mod day_2;
 mod day_3;
 mod day_4;
 mod day_5;
 mod day_6;
+mod example;
+mod puzzle;
+mod run_all;
 mod runner;
+mod solution;
+mod submission;
+mod table;
+mod wait;
 
 pub struct Lines {
     lines: Peekable<Box<dyn Iterator<Item = String>>>,
@@ -32,79 +46,244 @@ impl Lines {
         }
     }
 
+    pub fn from_string(s: &str) -> Self {
+        let lines = s.lines().map(|line| line.to_string()).collect::<Vec<_>>();
+        Lines::new(Box::new(lines.into_iter()))
+    }
+
     pub fn peek(&mut self) -> Option<&String> {
         self.lines.peek()
     }
 }
 
-fn adapt_implementation<T: 'static + Display>(
-    implementation: fn(Lines) -> Result<T>,
-) -> Box<dyn Fn(Lines) -> Result<String>> {
-    Box::new(move |lines| {
-        let result = implementation(lines)?;
-        Ok(format!("{result}"))
-    })
-}
-
 #[tokio::main]
 async fn main() -> Result<()> {
-    let opts: Opts = Opts::parse();
-    let runner = Runner::new()?;
-    let input = runner.get_input(opts.day, opts.force_download).await?;
-
-    let implementation = match (opts.day.day_number(), opts.part.part_number()) {
-        (1, 1) => adapt_implementation(day_1::day_1_1),
-        (1, 2) => adapt_implementation(day_1::day_1_2),
-        (2, 1) => adapt_implementation(day_2::day_2_1),
-        (2, 2) => adapt_implementation(day_2::day_2_2),
-        (3, 1) => adapt_implementation(day_3::day_3_1),
-        (3, 2) => adapt_implementation(day_3::day_3_2),
-        (4, 1) => adapt_implementation(day_4::day_4_1),
-        (4, 2) => adapt_implementation(day_4::day_4_2),
-        (5, 1) => adapt_implementation(day_5::day_5_1),
-        (5, 2) => adapt_implementation(day_5::day_5_2),
-        (6, 1) => adapt_implementation(day_6::day_6_1),
-        (6, 2) => adapt_implementation(day_6::day_6_2),
-        _ => panic!(
-            "Day {} part {} not implemented",
-            opts.day.day_number(),
-            opts.part.part_number()
-        ),
+    let opts = Opts::parse_args();
+    let config = Config::load(&opts)?;
+    let runner = Runner::new(
+        config.year.value,
+        opts.offline,
+        config.base_url.value.clone(),
+        config.cache_dir.value.clone(),
+        config.session.as_ref().map(|session| session.value.clone()),
+        config.cache_key.as_ref().map(|key| key.value.clone()),
+    );
+    let format = config.format.value;
+    match opts.command {
+        Some(Command::RunAll) => run_all::run_all(&runner, format).await,
+        Some(Command::Bench(bench_opts)) => bench::bench(&runner, bench_opts, format).await,
+        Some(Command::Puzzle(puzzle_opts)) => puzzle::puzzle(&runner, puzzle_opts).await,
+        Some(Command::Answers(answers_opts)) => answers::fetch(&runner, answers_opts).await,
+        Some(Command::Verify) => answers::verify(&runner, format).await,
+        Some(Command::Wait(wait_opts)) => wait::wait(&runner, wait_opts).await,
+        Some(Command::Cache(cache_command)) => cache::cache(&runner, cache_command, format).await,
+        Some(Command::Config(ConfigCommand::Show)) => {
+            config.show();
+            Ok(())
+        }
+        None => run(&runner, opts.run).await,
+    }
+}
+
+async fn run(runner: &Runner, opts: RunOpts) -> Result<()> {
+    let day = opts.day.expect("clap requires --day without a subcommand");
+    if opts.send && matches!(opts.part, Parts::Both) {
+        bail!("Choose the part to send with --part");
+    }
+    let parse = solution::find(runner.year(), day)?;
+
+    if opts.example {
+        let examples = runner.get_examples(day, opts.force_download).await?;
+        if examples.is_empty() {
+            bail!("No examples found for day {}", day.day_number());
+        }
+        return example::check(&examples, &opts.part.parts(), parse);
+    }
+    let lines = match &opts.input {
+        Some(path) => runner::read_input(path)?,
+        None => runner.get_input(day, opts.force_download).await?,
     };
 
-    let result = implementation(input)?;
+    let start = Instant::now();
+    let input = parse(lines)?;
+    println!("Parsed in {:?}", start.elapsed());
 
-    println!("{result}");
+    let mut results = Vec::new();
+    for part in opts.part.parts() {
+        println!();
+        if !input.is_implemented(part) {
+            println!("Part {} is not implemented yet", part.part_number());
+            continue;
+        }
+
+        let start = Instant::now();
+        let result = input.solve(part)?;
+        let solve_time = start.elapsed();
+
+        println!("Part {}: {result}", part.part_number());
+        println!("Solved in {solve_time:?}");
+        results.push((part, result));
+    }
     println!();
 
     if opts.send {
+        // --send takes a single part, so no result means that part isn't implemented
+        let Some((part, result)) = results.pop() else {
+            bail!("Nothing to send, the chosen part is not implemented yet");
+        };
+        send(runner, &opts, parse, day, part, result).await?;
+    }
+    Ok(())
+}
+
+async fn send(
+    runner: &Runner,
+    opts: &RunOpts,
+    parse: solution::Parser,
+    day: Day,
+    part: Part,
+    answer: String,
+) -> Result<()> {
+    // Refuse pointless answers before checking examples or waiting out a cooldown
+    let submissions = runner.submissions()?;
+    submissions.check(day, part, &answer)?;
+
+    if !opts.force {
+        let examples = runner.get_examples(day, false).await?;
+        if examples.iter().any(|example| example.part == part) {
+            if let Err(error) = example::check(&examples, &[part], parse) {
+                bail!("{error}, not sending the answer (use --force to send it anyway)");
+            }
+            println!();
+        } else {
+            println!("No examples to check the solution against");
+        }
+    }
+
+    let attempts = submissions.attempts(day, part).collect::<Vec<_>>();
+    if !attempts.is_empty() {
+        println!("Previous attempts:");
+        for attempt in attempts {
+            println!(
+                "  {}: {} ({})",
+                attempt.answer,
+                attempt.verdict,
+                attempt.age()
+            );
+        }
+        println!();
+    }
+    let bounds = submissions.bounds(day, part);
+    if !bounds.is_unbounded() {
+        println!("The answer is {bounds}");
+    }
+
+    if let Some(remaining) = submissions.cooldown(day) {
+        if !opts.wait {
+            bail!(
+                "Answers can't be sent for another {}, use --wait to send it then",
+                format_duration(remaining)
+            );
+        }
+        countdown(remaining).await?;
+    }
+
+    loop {
         println!("Sending the answer");
-        runner
-            .send_result(opts.day, opts.part, result.to_string())
-            .await?;
+        let verdict = runner.send_result(day, part, answer.clone()).await?;
+        println!("{verdict}");
+        match verdict {
+            Verdict::RateLimited { wait } if opts.wait => countdown(wait).await?,
+            Verdict::Correct => return Ok(()),
+            _ => process::exit(verdict.exit_code()),
+        }
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    if seconds >= 3600 {
+        format!(
+            "{}h {}m {}s",
+            seconds / 3600,
+            seconds % 3600 / 60,
+            seconds % 60
+        )
+    } else if seconds >= 60 {
+        format!("{}m {}s", seconds / 60, seconds % 60)
+    } else {
+        format!("{seconds}s")
     }
+}
+
+/// Sleeps through a cooldown or until an unlock, keeping the time left up to date
+/// on a single line.
+async fn countdown(duration: Duration) -> Result<()> {
+    // The site rounds the time left down, so leave it a moment of slack
+    let end = tokio::time::Instant::now() + duration + Duration::from_secs(1);
+    loop {
+        let remaining = end.saturating_duration_since(tokio::time::Instant::now());
+        if remaining.is_zero() {
+            break;
+        }
+        let shown = Duration::from_secs(remaining.as_secs_f64().ceil() as u64);
+        print!("\rWaiting {} ", format_duration(shown));
+        io::stdout().flush()?;
+        tokio::time::sleep(remaining.min(Duration::from_secs(1))).await;
+    }
+    println!("\rDone waiting{:20}", "");
     Ok(())
 }
 
 #[cfg(test)]
 pub mod tests {
     use super::*;
+    use crate::solution::Solution;
 
-    impl Lines {
-        pub fn from_text(s: &'static str) -> Self {
-            Lines::new(Box::new(s.lines().map(|line| line.to_string())))
-        }
-    }
-
-    pub fn aoc_test<Output: ToString>(
+    pub fn aoc_test<S: Solution + 'static>(
         input: &'static str,
-        implementation: fn(Lines) -> Result<Output>,
-        expected: Output,
+        part: Part,
+        expected: impl ToString,
     ) {
-        let lines = Lines::from_text(input);
+        let lines = Lines::from_string(input);
+        let input = solution::parse::<S>(lines).unwrap();
+        assert_eq!(input.solve(part).unwrap(), expected.to_string());
+    }
+
+    #[tokio::test]
+    async fn send_waits_out_rate_limit() {
+        let (url, log) = runner::tests::stand_in(&[
+            (
+                "/2022/day/1/answer",
+                "<main><article><p>You gave an answer too recently. You have 0s left to wait.</p></article></main>",
+            ),
+            (
+                "/2022/day/1/answer",
+                include_str!("../tests/fixtures/answer/correct.html"),
+            ),
+        ]);
+        let runner = runner::tests::runner("send-wait", url, false);
+        let opts = Opts::try_parse_args(["aoc", "-d", "1", "-p", "1", "-s", "-w", "--force"])
+            .unwrap()
+            .run;
+        let day = runner::tests::day("1");
+        let parse = solution::find(runner.year(), day).unwrap();
+
+        // Rate limited at first, then accepted once the wait is over
+        send(&runner, &opts, parse, day, Part::One, "24000".to_string())
+            .await
+            .unwrap();
+        assert_eq!(log.lock().unwrap().len(), 2);
+        assert!(runner.submissions().unwrap().is_solved(day, Part::One));
+    }
+
+    #[test]
+    fn durations() {
+        assert_eq!(format_duration(Duration::from_secs(42)), "42s");
+        assert_eq!(format_duration(Duration::from_secs(252)), "4m 12s");
         assert_eq!(
-            implementation(lines).unwrap().to_string(),
-            expected.to_string()
+            format_duration(Duration::from_secs(3 * 3600 + 5)),
+            "3h 0m 5s"
         );
     }
 }
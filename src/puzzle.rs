@@ -0,0 +1,143 @@
+use crate::runner::{PuzzleOpts, Runner};
+use anyhow::Result;
+use ego_tree::NodeRef;
+use reqwest::Url;
+use scraper::{ElementRef, Html, Node, Selector};
+
+pub async fn puzzle(runner: &Runner, opts: PuzzleOpts) -> Result<()> {
+    let html = runner.get_puzzle(opts.day, opts.force_download).await?;
+    println!();
+    println!("{}", to_markdown(&html, runner.base_url()));
+    Ok(())
+}
+
+fn descriptions(document: &Html) -> Vec<ElementRef<'_>> {
+    let selector = Selector::parse("article.day-desc").unwrap();
+    document.select(&selector).collect()
+}
+
+/// How many parts of the puzzle the page reveals. Part 2 only shows up once
+/// part 1 is solved.
+pub fn revealed_parts(html: &str) -> usize {
+    descriptions(&Html::parse_document(html)).len()
+}
+
+/// Renders the puzzle descriptions of a day page as markdown, with links to the
+/// site resolved against `base_url`.
+pub fn to_markdown(html: &str, base_url: &Url) -> String {
+    let document = Html::parse_document(html);
+    let mut markdown = String::new();
+    for article in descriptions(&document) {
+        for child in article.children() {
+            block(child, base_url, &mut markdown);
+        }
+    }
+    markdown.trim_end().to_string()
+}
+
+fn text(node: NodeRef<Node>) -> String {
+    ElementRef::wrap(node)
+        .map(|element| element.text().collect())
+        .unwrap_or_default()
+}
+
+fn block(node: NodeRef<Node>, base_url: &Url, out: &mut String) {
+    let Node::Element(element) = node.value() else {
+        if let Node::Text(text) = node.value() {
+            out.push_str(text.trim());
+        }
+        return;
+    };
+    match element.name() {
+        "h2" => out.push_str(&format!("## {}\n\n", text(node))),
+        "pre" => out.push_str(&format!("```\n{}```\n\n", text(node))),
+        "ul" => {
+            for item in node.children() {
+                if ElementRef::wrap(item).is_some() {
+                    out.push_str("- ");
+                    inline(item, base_url, out);
+                    out.push('\n');
+                }
+            }
+            out.push('\n');
+        }
+        _ => {
+            inline(node, base_url, out);
+            out.push_str("\n\n");
+        }
+    }
+}
+
+fn inline(node: NodeRef<Node>, base_url: &Url, out: &mut String) {
+    for child in node.children() {
+        match child.value() {
+            Node::Text(text) => out.push_str(text),
+            Node::Element(element) => match element.name() {
+                // Emphasis doesn't work inside code spans, so it goes outside
+                "code" if child.children().any(|c| c.value().is_element()) => {
+                    out.push_str(&format!("**`{}`**", text(child)))
+                }
+                "code" => out.push_str(&format!("`{}`", text(child))),
+                "em" => out.push_str(&format!("*{}*", text(child))),
+                "a" => {
+                    let href = element.attr("href").unwrap_or_default();
+                    // Site paths are kept under the base URL's own path
+                    let href = match href.strip_prefix('/') {
+                        Some(path) => base_url
+                            .join(path)
+                            .map_or(href.to_string(), |url| url.to_string()),
+                        None => href.to_string(),
+                    };
+                    out.push_str(&format!("[{}]({href})", text(child)));
+                }
+                _ => inline(child, base_url, out),
+            },
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::parse_base_url;
+
+    const PAGE: &str = include_str!("../tests/fixtures/puzzle/day_1.html");
+
+    #[test]
+    fn parts() {
+        assert_eq!(revealed_parts(PAGE), 2);
+    }
+
+    #[test]
+    fn markdown() {
+        let base_url = parse_base_url("https://adventofcode.com").unwrap();
+        let markdown = to_markdown(PAGE, &base_url);
+        assert!(markdown.starts_with(
+            "## --- Day 1: Calorie Counting ---
+
+The elves are taking inventory of their *snacks*."
+        ));
+        assert!(markdown.contains("([read more](https://adventofcode.com/2022/about))"));
+        assert!(markdown.contains(
+            "```
+1000
+2000
+
+4000
+```"
+        ));
+        assert!(markdown.contains(
+            "- The first elf carries items with `1000` and `2000` calories, a total of **`3000`**."
+        ));
+        assert!(markdown.ends_with("*How many calories are they carrying in total?*"));
+        assert!(!markdown.contains("Your puzzle answer was"));
+    }
+
+    #[test]
+    fn links_follow_base_url() {
+        let base_url = parse_base_url("http://localhost:8080/aoc").unwrap();
+        let markdown = to_markdown(PAGE, &base_url);
+        assert!(markdown.contains("([read more](http://localhost:8080/aoc/2022/about))"));
+    }
+}
@@ -0,0 +1,104 @@
+use crate::runner::{Day, Part, Runner};
+use crate::solution::{self, Parsed};
+use crate::table::{table, Format};
+use anyhow::{anyhow, bail, Result};
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::time::{Duration, Instant};
+
+struct Row {
+    day: Day,
+    // `None` when the day failed before any part could run
+    part: Option<Part>,
+    answer: Result<String>,
+    elapsed: Duration,
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+/// Runs `f`, turning a panic into an error so one broken day can't take down the
+/// whole run.
+pub fn isolate<T>(f: impl FnOnce() -> Result<T>) -> Result<T> {
+    panic::catch_unwind(AssertUnwindSafe(f))
+        .unwrap_or_else(|payload| Err(anyhow!("panicked: {}", panic_message(payload))))
+}
+
+async fn run_day(runner: &Runner, day: Day, parse: solution::Parser) -> Vec<Row> {
+    let start = Instant::now();
+    let input = match runner.get_input(day, false).await {
+        Ok(lines) => isolate(|| parse(lines)),
+        Err(error) => Err(error),
+    };
+    let input: Box<dyn Parsed> = match input {
+        Ok(input) => input,
+        Err(error) => {
+            return vec![Row {
+                day,
+                part: None,
+                answer: Err(error),
+                elapsed: start.elapsed(),
+            }]
+        }
+    };
+
+    [Part::One, Part::Two]
+        .into_iter()
+        .filter(|part| input.is_implemented(*part))
+        .map(|part| {
+            let start = Instant::now();
+            let answer = isolate(|| input.solve(part));
+            Row {
+                day,
+                part: Some(part),
+                answer,
+                elapsed: start.elapsed(),
+            }
+        })
+        .collect()
+}
+
+pub async fn run_all(runner: &Runner, format: Format) -> Result<()> {
+    let mut rows = Vec::new();
+    for day in Day::all() {
+        if let Ok(parse) = solution::find(runner.year(), day) {
+            rows.extend(run_day(runner, day, parse).await);
+        }
+    }
+
+    let failures = rows.iter().filter(|row| row.answer.is_err()).count();
+    let rows = rows
+        .into_iter()
+        .map(|row| {
+            vec![
+                row.day.day_number().to_string(),
+                row.part
+                    .map(|part| part.part_number().to_string())
+                    .unwrap_or_else(|| "-".to_string()),
+                match row.answer {
+                    Ok(answer) => answer,
+                    Err(error) => format!("error: {error:#}"),
+                },
+                format!("{:?}", row.elapsed),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    println!();
+    println!(
+        "{}",
+        table(format, &["Day", "Part", "Answer", "Time"], &rows)
+    );
+
+    if failures > 0 {
+        bail!("{failures} of {} solutions failed", rows.len());
+    }
+    Ok(())
+}
@@ -1,22 +1,29 @@
-use std::env;
-
+use std::fmt::{Display, Formatter};
 use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::path::Path;
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::sync::Arc;
 
-use anyhow::{anyhow, Result};
-use clap::Parser;
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, TimeZone, Utc};
+use clap::parser::ValueSource;
+use clap::{ArgMatches, Args, CommandFactory, FromArgMatches, Parser, Subcommand};
+use once_cell::sync::OnceCell;
 
+use crate::cache::{self, CacheKey};
+use crate::config::parse_base_url;
+use crate::example::{self, Example};
+use crate::puzzle;
+use crate::submission::{Submission, Submissions, Verdict};
+use crate::table::Format;
 use crate::Lines;
 use reqwest::cookie::Jar;
-use reqwest::{Client, ClientBuilder, Url};
-use scraper::{Html, Selector};
-use tokio::fs::{create_dir_all, File as TokioFile};
-use tokio::io::AsyncWriteExt;
+use reqwest::header::CONTENT_TYPE;
+use reqwest::{Client, ClientBuilder, StatusCode, Url};
+use tokio::fs::{create_dir_all, read_to_string};
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Part {
     One,
     Two,
@@ -44,116 +51,1126 @@ impl FromStr for Part {
 }
 
 #[derive(Clone, Copy, Debug)]
+pub enum Parts {
+    Single(Part),
+    Both,
+}
+
+impl Parts {
+    pub fn parts(&self) -> Vec<Part> {
+        match self {
+            Parts::Single(part) => vec![*part],
+            Parts::Both => vec![Part::One, Part::Two],
+        }
+    }
+}
+
+impl FromStr for Parts {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "both" => Ok(Parts::Both),
+            _ => s
+                .parse()
+                .map(Parts::Single)
+                .map_err(|_| "The part must be either 'one', 1, 'two', 2 or 'both'"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct Day(u8);
 
 impl Day {
     pub fn day_number(&self) -> u8 {
         self.0
     }
+
+    pub fn all() -> impl Iterator<Item = Day> {
+        (1..=25).map(Day)
+    }
 }
 
-fn parse_day(s: &str) -> Result<Day, &'static str> {
+pub fn parse_day(s: &str) -> Result<Day, &'static str> {
     match u8::from_str(s) {
         Ok(day) if (1..=25).contains(&day) => Ok(Day(day)),
         _ => Err("The day must be a number from 1 to 25"),
     }
 }
 
-#[derive(Parser, Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Year(u16);
+
+impl Year {
+    pub fn year_number(&self) -> u16 {
+        self.0
+    }
+
+    /// When the puzzle of `day` unlocks: midnight US Eastern time, which is UTC-5
+    /// all through December.
+    pub fn unlock_time(&self, day: Day) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(self.0.into(), 12, day.day_number().into(), 5, 0, 0)
+            .unwrap()
+    }
+
+    /// The days of the event, which has 12 from 2025 on instead of 25.
+    pub fn days(&self) -> impl Iterator<Item = Day> {
+        let last = if self.0 >= 2025 { 12 } else { 25 };
+        (1..=last).map(Day)
+    }
+
+    /// The days whose puzzles are unlocked at `now`.
+    pub fn unlocked_days(&self, now: DateTime<Utc>) -> impl Iterator<Item = Day> + '_ {
+        self.days().filter(move |day| self.unlock_time(*day) <= now)
+    }
+}
+
+pub fn parse_year(s: &str) -> Result<Year, &'static str> {
+    match u16::from_str(s) {
+        Ok(year) if year >= 2015 => Ok(Year(year)),
+        _ => Err("The year must be 2015 or later, when Advent of Code started"),
+    }
+}
+
+#[derive(Parser, Clone, Debug)]
 #[command(author, version, about, long_about = None)]
+#[command(subcommand_negates_reqs = true)]
 pub struct Opts {
+    /// The Advent of Code event to run [default: 2022]
+    #[arg(short, long, global = true, value_parser(parse_year))]
+    pub year: Option<Year>,
+    /// Where inputs, puzzles and submissions are kept [default: inputs]
+    #[arg(long, global = true)]
+    pub cache_dir: Option<PathBuf>,
+    /// The site to talk to [default: https://adventofcode.com/]
+    #[arg(long, global = true, value_parser(parse_base_url))]
+    pub base_url: Option<Url>,
+    /// How tables are printed, either markdown or tsv [default: markdown]
+    #[arg(long, global = true)]
+    pub format: Option<Format>,
+    /// Never access the network, only use what is already cached
+    #[arg(long, global = true)]
+    pub offline: bool,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    #[command(flatten)]
+    pub run: RunOpts,
+}
+
+impl Opts {
+    /// Parses the command line, exiting with a usage error when it's invalid.
+    pub fn parse_args() -> Self {
+        Self::try_parse_args(std::env::args_os()).unwrap_or_else(|error| error.exit())
+    }
+
+    /// Parses `args` like clap would, but also refuses the options of a single-day
+    /// run along with a subcommand, which would otherwise be silently ignored.
+    pub fn try_parse_args<I, T>(args: I) -> Result<Self, clap::Error>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<std::ffi::OsString> + Clone,
+    {
+        let mut command = Self::command();
+        let matches = command.try_get_matches_from_mut(args)?;
+        if let Some((subcommand, _)) = matches.subcommand() {
+            if let Some(arg) = run_only_arg(&command, &matches) {
+                return Err(command.error(
+                    clap::error::ErrorKind::ArgumentConflict,
+                    format!("{arg} can't be used with the {subcommand} subcommand"),
+                ));
+            }
+        }
+        Self::from_arg_matches(&matches)
+    }
+}
+
+/// The first option given on the command line which only applies when running a
+/// single day, such as `--day`.
+fn run_only_arg(command: &clap::Command, matches: &ArgMatches) -> Option<String> {
+    command
+        .get_arguments()
+        .filter(|arg| !arg.is_global_set())
+        .find(|arg| matches.value_source(arg.get_id().as_str()) == Some(ValueSource::CommandLine))
+        .map(|arg| match arg.get_long() {
+            Some(long) => format!("--{long}"),
+            None => arg.get_id().to_string(),
+        })
+}
+
+#[derive(Subcommand, Clone, Debug)]
+pub enum Command {
+    /// Run every implemented day and print a summary table
+    RunAll,
+    /// Time the parse and solve phases over many iterations
+    Bench(BenchOpts),
+    /// Print the puzzle description as markdown
+    Puzzle(PuzzleOpts),
+    /// Record the accepted answers shown on the puzzle pages of implemented days
+    Answers(AnswersOpts),
+    /// Check every implemented solution against the recorded answers
+    Verify,
+    /// Inspect the configuration
+    #[command(subcommand)]
+    Config(ConfigCommand),
+    /// Inspect and manage the cached inputs and puzzles
+    #[command(subcommand)]
+    Cache(CacheCommand),
+    /// Wait for a puzzle to unlock and download its input the moment it does
+    Wait(WaitOpts),
+}
+
+#[derive(Subcommand, Clone, Copy, Debug)]
+pub enum ConfigCommand {
+    /// Print the effective settings and where each of them comes from
+    Show,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct RunOpts {
+    // Only optional when a subcommand is given
+    #[arg(short, long, value_parser(parse_day), required = true)]
+    pub day: Option<Day>,
+    #[arg(short, long, default_value = "both")]
+    pub part: Parts,
+    #[arg(short, long)]
+    pub force_download: bool,
+    /// Read the input from this file, or from stdin if it's `-`, instead of the cache
+    #[arg(short, long, conflicts_with_all = ["force_download", "example", "send"])]
+    pub input: Option<PathBuf>,
+    #[arg(short, long)]
+    pub send: bool,
+    /// Run against the examples from the puzzle description instead of the input
+    #[arg(short, long, conflicts_with = "send")]
+    pub example: bool,
+    /// Send the answer even if the solution gets the examples wrong
+    #[arg(long, requires = "send")]
+    pub force: bool,
+    /// When rate limited, wait for the cooldown to pass and send the answer then
+    #[arg(short, long, requires = "send")]
+    pub wait: bool,
+}
+
+#[derive(Args, Clone, Copy, Debug)]
+pub struct BenchOpts {
+    /// Benchmark only this day instead of every implemented one
     #[arg(short, long, value_parser(parse_day))]
-    pub day: Day,
+    pub day: Option<Day>,
+    #[arg(short, long, default_value = "both")]
+    pub part: Parts,
+    #[arg(short, long, default_value_t = 100)]
+    pub iterations: usize,
+    /// Untimed iterations run before measuring
+    #[arg(short, long, default_value_t = 10)]
+    pub warmup: usize,
+    /// Compare against the last recorded results instead of recording new ones,
+    /// failing on regressions
     #[arg(short, long)]
-    pub part: Part,
+    pub compare: bool,
+    /// How many percent slower than the baseline counts as a regression
+    #[arg(short, long, default_value_t = 10.0)]
+    pub threshold: f64,
+}
+
+#[derive(Args, Clone, Copy, Debug)]
+pub struct PuzzleOpts {
+    #[arg(short, long, value_parser(parse_day))]
+    pub day: Day,
     #[arg(short, long)]
     pub force_download: bool,
+}
+
+#[derive(Args, Clone, Copy, Debug)]
+pub struct AnswersOpts {
+    /// Seconds to wait between downloads, to go easy on the site
+    #[arg(long, default_value_t = 3)]
+    pub delay: u64,
+}
+
+#[derive(Args, Clone, Copy, Debug)]
+pub struct WaitOpts {
+    /// The day to wait for, by default the next one to unlock
+    #[arg(short, long, value_parser(parse_day))]
+    pub day: Option<Day>,
+    /// Run the solution once the input is downloaded
     #[arg(short, long)]
-    pub send: bool,
+    pub run: bool,
+    /// The parts to run
+    #[arg(short, long, default_value = "both")]
+    pub part: Parts,
+}
+
+#[derive(Subcommand, Clone, Copy, Debug)]
+pub enum CacheCommand {
+    /// List the cached inputs of every year
+    List,
+    /// Check the cached inputs of every year against their checksums and for
+    /// login pages or empty files
+    Verify,
+    /// Delete the cached inputs and puzzle pages of the year
+    Clear(ClearOpts),
+    /// Download the inputs of the year which aren't cached yet
+    Prefetch(PrefetchOpts),
+    /// Encrypt the plaintext inputs of every year with the cache key
+    Encrypt,
+    /// Decrypt the encrypted inputs of every year back into plaintext
+    Decrypt,
+}
+
+#[derive(Args, Clone, Copy, Debug)]
+pub struct ClearOpts {
+    /// Only clear this day instead of the whole year
+    #[arg(short, long, value_parser(parse_day))]
+    pub day: Option<Day>,
+}
+
+#[derive(Args, Clone, Copy, Debug)]
+pub struct PrefetchOpts {
+    #[arg(short, long, value_parser(parse_day), required_unless_present = "all")]
+    pub day: Option<Day>,
+    /// Download every day that is unlocked
+    #[arg(short, long, conflicts_with = "day")]
+    pub all: bool,
+    /// Seconds to wait between downloads, to go easy on the site
+    #[arg(long, default_value_t = 3)]
+    pub delay: u64,
 }
 
 fn line_reader(path: &Path) -> Result<Lines> {
     let file = File::open(path)?;
-    Ok(Lines::new(Box::new(BufReader::new(file).lines().flatten())))
+    Ok(Lines::new(Box::new(
+        BufReader::new(file).lines().map_while(Result::ok),
+    )))
+}
+
+/// Reads the input from any file, or from stdin when `path` is `-`.
+pub fn read_input(path: &Path) -> Result<Lines> {
+    if path == Path::new("-") {
+        return Ok(Lines::new(Box::new(
+            io::stdin().lines().map_while(Result::ok),
+        )));
+    }
+    line_reader(path).with_context(|| format!("Can't read input from {}", path.display()))
+}
+
+/// Why a downloaded input was refused instead of being cached.
+#[derive(Debug, PartialEq, Eq)]
+pub enum InputError {
+    /// The site didn't take the session token, most likely because it expired
+    SessionExpired,
+    /// The site has no input for the day, most likely because it isn't unlocked yet
+    NotFound,
+    Unexpected(String),
+}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputError::SessionExpired => write!(
+                f,
+                "The session token was not accepted, it has probably expired. \
+                 Log in to the site again and refresh SESSION_COOKIE or `session` in aoc.toml"
+            ),
+            InputError::NotFound => write!(f, "No input yet, the puzzle may not be unlocked"),
+            InputError::Unexpected(response) => {
+                write!(f, "Got {response} instead of the puzzle input")
+            }
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+/// Messages the site answers with instead of the input when it doesn't know who
+/// is asking, or shows on puzzle pages in place of the user's progress.
+const LOGGED_OUT_MESSAGES: &[&str] = &[
+    "Puzzle inputs differ by user",
+    "Please log in",
+    "please identify yourself",
+];
+
+/// Makes sure a downloaded body is an actual puzzle input. Expired sessions get
+/// a login message or page back, which must not end up in the cache.
+fn check_input(
+    status: StatusCode,
+    content_type: Option<&str>,
+    body: &str,
+) -> Result<(), InputError> {
+    if !logged_out(body) {
+        if status == StatusCode::NOT_FOUND {
+            return Err(InputError::NotFound);
+        }
+        if !status.is_success() {
+            return Err(InputError::Unexpected(format!("HTTP status {status}")));
+        }
+    }
+    if content_type.is_some_and(|content_type| content_type.starts_with("text/html")) {
+        return Err(InputError::SessionExpired);
+    }
+    check_body(body)
+}
+
+fn logged_out(body: &str) -> bool {
+    LOGGED_OUT_MESSAGES
+        .iter()
+        .any(|message| body.contains(message))
+}
+
+/// Checks that `body` looks like a puzzle input rather than a login message or
+/// page, whether it was just downloaded or is already cached.
+pub fn check_body(body: &str) -> Result<(), InputError> {
+    let start = body.trim_start().to_ascii_lowercase();
+    // Without a valid session the site redirects to its login page
+    if logged_out(body) || start.starts_with("<!doctype") || start.starts_with("<html") {
+        return Err(InputError::SessionExpired);
+    }
+    if body.trim().is_empty() {
+        return Err(InputError::Unexpected("nothing".to_string()));
+    }
+    Ok(())
 }
 
 pub struct Runner {
-    client: Client,
+    year: Year,
+    offline: bool,
+    base_url: Url,
+    inputs_dir: PathBuf,
+    session: Option<String>,
+    cache_key: Option<CacheKey>,
+    // Only built once something has to be downloaded or sent, so cached inputs
+    // work without a session cookie
+    client: OnceCell<Client>,
 }
 
 impl Runner {
-    pub fn new() -> Result<Self> {
-        let session_key = env::var("SESSION_COOKIE")
-            .map_err(|_| anyhow!("SESSION_COOKIE environment variable is not set"))?;
-        let cookie = format!("session={session_key}; Domain=.adventofcode.com");
-        let url = "https://adventofcode.com/".parse::<Url>()?;
+    /// A runner for the `year` event of the site at `base_url`, keeping its files
+    /// in `inputs_dir`. The `session` token is only needed once something has to
+    /// be downloaded or sent, and inputs are cached encrypted with `cache_key`
+    /// when there is one.
+    pub fn new(
+        year: Year,
+        offline: bool,
+        base_url: Url,
+        inputs_dir: PathBuf,
+        session: Option<String>,
+        cache_key: Option<CacheKey>,
+    ) -> Self {
+        Self {
+            year,
+            offline,
+            base_url,
+            inputs_dir,
+            session,
+            cache_key,
+            client: OnceCell::new(),
+        }
+    }
+
+    pub fn year(&self) -> Year {
+        self.year
+    }
 
-        let jar = Jar::default();
-        jar.add_cookie_str(&cookie, &url);
+    pub fn base_url(&self) -> &Url {
+        &self.base_url
+    }
 
-        let client = ClientBuilder::new()
-            .cookie_provider(Arc::new(jar))
-            .build()?;
+    pub fn cache_key(&self) -> Result<&CacheKey> {
+        self.cache_key.as_ref().ok_or_else(|| {
+            anyhow!("No cache key is set, it's needed for encrypted inputs. Set the AOC_CACHE_KEY environment variable or `cache_key` in aoc.toml")
+        })
+    }
+
+    /// Where the directories of every year are kept.
+    pub fn inputs_dir(&self) -> &Path {
+        &self.inputs_dir
+    }
 
-        Ok(Self { client })
+    /// Where everything downloaded or recorded for the year is kept.
+    pub fn cache_dir(&self) -> PathBuf {
+        self.inputs_dir.join(self.year.year_number().to_string())
     }
 
+    pub fn input_path(&self, day: Day) -> PathBuf {
+        self.cache_dir()
+            .join(format!("day-{}-input.txt", day.day_number()))
+    }
+
+    pub fn puzzle_path(&self, day: Day) -> PathBuf {
+        self.cache_dir()
+            .join(format!("day-{}-puzzle.html", day.day_number()))
+    }
+
+    fn day_url(&self, day: Day, path: &str) -> Result<Url> {
+        Ok(self.base_url.join(&format!(
+            "{}/day/{}{path}",
+            self.year.year_number(),
+            day.day_number()
+        ))?)
+    }
+
+    /// The HTTP client, for when `purpose` can't be done without the network.
+    fn client(&self, purpose: &str) -> Result<&Client> {
+        if self.offline {
+            bail!("Can't {purpose} in offline mode");
+        }
+        self.client.get_or_try_init(|| {
+            let session_key = self.session.as_ref().ok_or_else(|| {
+                anyhow!("No session token is set, it's needed to {purpose}. Set the SESSION_COOKIE environment variable or `session` in aoc.toml")
+            })?;
+            let jar = Jar::default();
+            jar.add_cookie_str(&format!("session={session_key}"), &self.base_url);
+
+            Ok(ClientBuilder::new()
+                .cookie_provider(Arc::new(jar))
+                .build()?)
+        })
+    }
+
+    /// Returns the day's input, from the cache when it's there. With a cache key
+    /// set, downloaded inputs are cached encrypted.
     pub async fn get_input(&self, day: Day, force_download: bool) -> Result<Lines> {
-        let path = format!("day-{}-input.txt", day.day_number());
-        let path = Path::new(".").join("inputs").join(&path);
+        let path = self.input_path(day);
+        let encrypted_path = cache::encrypted_path(&path);
         let prefix = path.parent().unwrap();
         create_dir_all(prefix).await?;
-        let path = path.as_path();
 
-        if !force_download && path.exists() {
-            println!("Using cached input for day {}", day.day_number());
-            return line_reader(path);
+        if !force_download {
+            for cached in [&encrypted_path, &path] {
+                if cached.exists() {
+                    println!("Using cached input for day {}", day.day_number());
+                    return self.read_cached_input(day, cached);
+                }
+            }
         }
 
+        self.ensure_unlocked(day)?;
+        let client = self.client(&format!("download the input for day {}", day.day_number()))?;
         println!("Downloading input for day {}", day.day_number());
-        let url = format!(
-            "https://adventofcode.com/2022/day/{}/input",
-            day.day_number()
-        )
-        .parse::<Url>()?;
-        let resp = self.client.get(url).send().await?;
-        resp.error_for_status_ref()?;
+        let url = self.day_url(day, "/input")?;
+        let resp = client.get(url).send().await?;
+        let status = resp.status();
+        let content_type = resp
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|content_type| content_type.to_str().ok())
+            .map(str::to_string);
+        let text = resp.text().await?;
+        check_input(status, content_type.as_deref(), &text)?;
         println!("Input downloaded");
 
-        let mut file = TokioFile::create(path).await?;
-        file.write_all(resp.text().await.unwrap().as_bytes())
-            .await?;
-        file.sync_all().await?;
+        // Only one of the two may be cached, or the stale one could be read later
+        match &self.cache_key {
+            Some(key) => {
+                cache::store(&encrypted_path, &cache::encrypt(key, text.as_bytes())?)?;
+                cache::remove(&path)?;
+            }
+            None => {
+                cache::store(&path, text.as_bytes())?;
+                cache::remove(&encrypted_path)?;
+            }
+        }
+        Ok(Lines::from_string(&text))
+    }
+
+    /// Fails for days whose puzzle isn't out yet, instead of asking the site for
+    /// something it can't have.
+    fn ensure_unlocked(&self, day: Day) -> Result<()> {
+        if !self.year.days().any(|event_day| event_day == day) {
+            bail!(
+                "There is no day {} in {}",
+                day.day_number(),
+                self.year.year_number()
+            );
+        }
+        let unlock_time = self.year.unlock_time(day);
+        if Utc::now() < unlock_time {
+            bail!(
+                "Day {} of {} isn't unlocked until {}, use the wait command to download it as soon as it is",
+                day.day_number(),
+                self.year.year_number(),
+                unlock_time.format("%Y-%m-%d %H:%M UTC")
+            );
+        }
+        Ok(())
+    }
+
+    fn read_cached_input(&self, day: Day, path: &Path) -> Result<Lines> {
+        if let cache::Check::Mismatch(problem) = cache::check(path)? {
+            eprintln!(
+                "Warning: the cached input for day {} {problem}, use --force-download to download it again",
+                day.day_number()
+            );
+        }
+        if path.extension().is_some_and(|extension| extension == "enc") {
+            let plaintext = cache::decrypt(self.cache_key()?, &std::fs::read(path)?)
+                .with_context(|| format!("Can't read {}", path.display()))?;
+            return Ok(Lines::from_string(&String::from_utf8(plaintext)?));
+        }
         line_reader(path)
     }
 
-    pub async fn send_result(&self, day: Day, part: Part, result: String) -> Result<()> {
-        let url = format!(
-            "https://adventofcode.com/2022/day/{}/answer",
-            day.day_number()
-        )
-        .parse::<Url>()?;
+    /// Returns the HTML of the day's puzzle page. A cached page is downloaded again
+    /// once part 1 is solved, since only then does it include part 2, but is still
+    /// used if that download fails.
+    pub async fn get_puzzle(&self, day: Day, force_download: bool) -> Result<String> {
+        let path = self.puzzle_path(day);
+        let prefix = path.parent().unwrap();
+        create_dir_all(prefix).await?;
+
+        if !force_download && path.exists() {
+            let html = read_to_string(&path).await?;
+            if self.offline
+                || puzzle::revealed_parts(&html) >= 2
+                || !self.submissions()?.is_solved(day, Part::One)
+            {
+                println!("Using cached puzzle for day {}", day.day_number());
+                return Ok(html);
+            }
+            return match self.download_puzzle(day, &path).await {
+                Ok(html) => Ok(html),
+                Err(error) => {
+                    eprintln!(
+                        "Warning: can't download the puzzle for day {} again ({error:#}), using the cached one",
+                        day.day_number()
+                    );
+                    Ok(html)
+                }
+            };
+        }
+        self.download_puzzle(day, &path).await
+    }
+
+    async fn download_puzzle(&self, day: Day, path: &Path) -> Result<String> {
+        self.ensure_unlocked(day)?;
+        let client = self.client(&format!("download the puzzle for day {}", day.day_number()))?;
+        println!("Downloading puzzle for day {}", day.day_number());
+        let url = self.day_url(day, "")?;
+        let resp = client.get(url).send().await?;
+        resp.error_for_status_ref()?;
+        let html = resp.text().await?;
+        // A logged out page never reveals part 2, so it mustn't replace the cached one
+        if logged_out(&html) {
+            return Err(InputError::SessionExpired.into());
+        }
+
+        cache::write_atomic(path, html.as_bytes())?;
+        Ok(html)
+    }
+
+    /// Returns the examples for the day, scraping them from the puzzle page the
+    /// first time.
+    pub async fn get_examples(&self, day: Day, force_download: bool) -> Result<Vec<Example>> {
+        let dir = self
+            .cache_dir()
+            .join("examples")
+            .join(format!("day-{}", day.day_number()));
+        let scraped = example::load(&dir)?
+            .iter()
+            .filter(|example| example.name == "scraped")
+            .count();
+
+        let html = self.get_puzzle(day, force_download).await?;
+        if scraped < puzzle::revealed_parts(&html) {
+            println!("Scraping examples for day {}", day.day_number());
+            example::save(&dir, &example::scrape(&html))?;
+        }
+        example::load(&dir)
+    }
+
+    pub fn submissions(&self) -> Result<Submissions> {
+        Submissions::load(&self.cache_dir().join("submissions.tsv"))
+    }
+
+    /// Sends the answer unless the submission history shows it would be pointless,
+    /// and records the verdict.
+    pub async fn send_result(&self, day: Day, part: Part, result: String) -> Result<Verdict> {
+        let mut submissions = self.submissions()?;
+        submissions.check(day, part, &result)?;
+
+        let url = self.day_url(day, "/answer")?;
         let resp = self
-            .client
+            .client("send an answer")?
             .post(url)
             .form(&[
                 ("level", part.part_number().to_string()),
-                ("answer", result),
+                ("answer", result.clone()),
             ])
             .send()
             .await?;
         resp.error_for_status_ref()?;
 
-        let document = Html::parse_document(&resp.text().await?);
-        let selector = Selector::parse("body > main > article > p:nth-child(1)").unwrap();
-        let response_text = document
-            .select(&selector)
-            .next()
-            .ok_or_else(|| anyhow!("No content!"))?
-            .text()
-            .collect::<Vec<_>>()
-            .join("\n");
-        println!("{response_text}");
-        Ok(())
+        let verdict = Verdict::parse(&resp.text().await?, part)?;
+        submissions.record(Submission::new(day, part, result, verdict)?)?;
+        Ok(verdict)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use hyper::service::{make_service_fn, service_fn};
+    use hyper::{Body, Request, Response, Server, StatusCode};
+    use std::convert::Infallible;
+    use std::env;
+    use std::process;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    const SESSION: &str = "test-session";
+
+    #[derive(Clone, Debug)]
+    pub struct Received {
+        pub method: String,
+        pub path: String,
+        pub cookie: Option<String>,
+        pub body: String,
+    }
+
+    pub type Log = Arc<Mutex<Vec<Received>>>;
+
+    /// Serves `pages` by path on a local port, answering anything else with a 404,
+    /// and logs the requests it gets. A path listed more than once gets its pages
+    /// in turn, the last one repeating.
+    pub fn stand_in(pages: &[(&'static str, &'static str)]) -> (Url, Log) {
+        let pages = pages.to_vec();
+        let log = Log::default();
+        let server_log = log.clone();
+        let make_service = make_service_fn(move |_| {
+            let pages = pages.clone();
+            let log = server_log.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request: Request<Body>| {
+                    let pages = pages.clone();
+                    let log = log.clone();
+                    async move {
+                        let method = request.method().to_string();
+                        let path = request.uri().path().to_string();
+                        let cookie = request
+                            .headers()
+                            .get("cookie")
+                            .map(|cookie| cookie.to_str().unwrap().to_string());
+                        let body = hyper::body::to_bytes(request.into_body()).await.unwrap();
+                        let mut log = log.lock().unwrap();
+                        let earlier = log.iter().filter(|received| received.path == path).count();
+                        log.push(Received {
+                            method,
+                            path: path.clone(),
+                            cookie,
+                            body: String::from_utf8_lossy(&body).to_string(),
+                        });
+
+                        let matching = pages
+                            .iter()
+                            .filter(|(page, _)| *page == path)
+                            .collect::<Vec<_>>();
+                        let response = match matching.get(earlier).or(matching.last()) {
+                            Some((_, content)) => Response::new(Body::from(*content)),
+                            None => Response::builder()
+                                .status(StatusCode::NOT_FOUND)
+                                .body(Body::from("404 Not Found"))
+                                .unwrap(),
+                        };
+                        Ok::<_, Infallible>(response)
+                    }
+                }))
+            }
+        });
+        let server = Server::bind(&([127, 0, 0, 1], 0).into()).serve(make_service);
+        let url = parse_base_url(&format!("http://{}", server.local_addr())).unwrap();
+        tokio::spawn(server);
+        (url, log)
+    }
+
+    pub fn runner(name: &str, base_url: Url, offline: bool) -> Runner {
+        let dir = env::temp_dir().join(format!("aoc-runner-{}-{name}", process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        Runner::new(
+            parse_year("2022").unwrap(),
+            offline,
+            base_url,
+            dir,
+            Some(SESSION.to_string()),
+            None,
+        )
+    }
+
+    pub fn day(day: &str) -> Day {
+        parse_day(day).unwrap()
+    }
+
+    #[tokio::test]
+    async fn downloads_and_caches_input() {
+        let (url, log) = stand_in(&[("/2022/day/1/input", "1000\n2000\n\n4000\n")]);
+        let runner = runner("input", url, false);
+
+        let lines = runner.get_input(day("1"), false).await.unwrap();
+        assert_eq!(lines.collect::<Vec<_>>(), ["1000", "2000", "", "4000"]);
+        let received = log.lock().unwrap().clone();
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0].method, "GET");
+        assert_eq!(received[0].cookie.as_deref(), Some("session=test-session"));
+        let path = runner.cache_dir().join("day-1-input.txt");
+        assert_eq!(cache::check(&path).unwrap(), cache::Check::Intact);
+
+        runner.get_input(day("1"), false).await.unwrap();
+        assert_eq!(log.lock().unwrap().len(), 1);
+        runner.get_input(day("1"), true).await.unwrap();
+        assert_eq!(log.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn caches_input_encrypted() {
+        let (url, log) = stand_in(&[("/2022/day/1/input", "1000\n2000\n")]);
+        let mut runner = runner("encrypted", url, false);
+        let key = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+        runner.cache_key = Some(cache::parse_cache_key(key).unwrap());
+
+        let lines = runner.get_input(day("1"), false).await.unwrap();
+        assert_eq!(lines.collect::<Vec<_>>(), ["1000", "2000"]);
+        let path = runner.input_path(day("1"));
+        assert!(!path.exists());
+        let encrypted = std::fs::read(cache::encrypted_path(&path)).unwrap();
+        assert!(!String::from_utf8_lossy(&encrypted).contains("1000"));
+
+        let lines = runner.get_input(day("1"), false).await.unwrap();
+        assert_eq!(lines.collect::<Vec<_>>(), ["1000", "2000"]);
+        assert_eq!(log.lock().unwrap().len(), 1);
+
+        runner.cache_key = None;
+        assert!(runner.get_input(day("1"), false).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn locked_days_are_not_requested() {
+        let (url, log) = stand_in(&[("/2099/day/1/input", "1\n")]);
+        let mut runner = runner("locked", url, false);
+        runner.year = parse_year("2099").unwrap();
+
+        assert!(runner.get_input(day("1"), false).await.is_err());
+        assert!(runner.get_puzzle(day("1"), false).await.is_err());
+        // Events from 2025 on end on day 12
+        runner.year = parse_year("2025").unwrap();
+        assert!(runner.get_input(day("13"), false).await.is_err());
+        assert!(log.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn missing_input_is_not_cached() {
+        let (url, _) = stand_in(&[]);
+        let runner = runner("missing", url, false);
+
+        let error = runner.get_input(day("2"), false).await.err().unwrap();
+        assert_eq!(error.downcast_ref(), Some(&InputError::NotFound));
+        assert!(!runner.cache_dir().join("day-2-input.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn expired_session_is_not_cached() {
+        let (url, _) = stand_in(&[
+            (
+                "/2022/day/1/input",
+                "Puzzle inputs differ by user.  Please log in to get your puzzle input.\n",
+            ),
+            (
+                "/2022/day/2/input",
+                "<!DOCTYPE html>\n<html><body>[Log In]</body></html>",
+            ),
+        ]);
+        let runner = runner("expired", url, false);
+
+        for day_number in ["1", "2"] {
+            let error = runner
+                .get_input(day(day_number), false)
+                .await
+                .err()
+                .unwrap();
+            assert_eq!(error.downcast_ref(), Some(&InputError::SessionExpired));
+            let path = format!("day-{day_number}-input.txt");
+            assert!(!runner.cache_dir().join(path).exists());
+        }
+    }
+
+    /// A puzzle page with only part 1 revealed, as the site shows it before part 1
+    /// is solved.
+    const PART_1_PAGE: &str = concat!(
+        "<main><article class=\"day-desc\">",
+        include_str!("../tests/fixtures/mock/2022/day-1/part-1.html"),
+        "</article></main>"
+    );
+
+    #[tokio::test]
+    async fn cached_puzzle_is_used_until_part_1_is_solved() {
+        let (url, log) = stand_in(&[("/2022/day/1", PART_1_PAGE)]);
+        let mut runner = runner("part-2", url, false);
+        runner.get_puzzle(day("1"), false).await.unwrap();
+        assert_eq!(log.lock().unwrap().len(), 1);
+
+        // No session is needed for what's cached
+        runner.session = None;
+        assert_eq!(
+            runner.get_puzzle(day("1"), false).await.unwrap(),
+            PART_1_PAGE
+        );
+        assert_eq!(log.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn cached_puzzle_is_used_when_it_cant_be_downloaded_again() {
+        let (url, log) = stand_in(&[]);
+        let runner = runner("part-2-missing", url, false);
+        std::fs::create_dir_all(runner.cache_dir()).unwrap();
+        std::fs::write(runner.puzzle_path(day("1")), PART_1_PAGE).unwrap();
+        let solved = Submission::new(day("1"), Part::One, "24000".to_string(), Verdict::Correct);
+        runner
+            .submissions()
+            .unwrap()
+            .record(solved.unwrap())
+            .unwrap();
+
+        assert_eq!(
+            runner.get_puzzle(day("1"), false).await.unwrap(),
+            PART_1_PAGE
+        );
+        assert_eq!(log.lock().unwrap().len(), 1);
+    }
+
+    /// The same puzzle page once part 1 is solved.
+    const PART_2_PAGE: &str = concat!(
+        "<main><article class=\"day-desc\">",
+        include_str!("../tests/fixtures/mock/2022/day-1/part-1.html"),
+        "</article><p>Your puzzle answer was <code>24000</code>.</p>",
+        "<article class=\"day-desc\">",
+        include_str!("../tests/fixtures/mock/2022/day-1/part-2.html"),
+        "</article></main>"
+    );
+
+    #[tokio::test]
+    async fn downloads_and_caches_puzzle() {
+        let (url, log) = stand_in(&[("/2022/day/1", PART_2_PAGE)]);
+        let runner = runner("puzzle", url, false);
+
+        assert_eq!(
+            runner.get_puzzle(day("1"), false).await.unwrap(),
+            PART_2_PAGE
+        );
+        let received = log.lock().unwrap().clone();
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0].path, "/2022/day/1");
+        assert_eq!(received[0].cookie.as_deref(), Some("session=test-session"));
+        let cached = std::fs::read_to_string(runner.puzzle_path(day("1"))).unwrap();
+        assert_eq!(cached, PART_2_PAGE);
+
+        runner.get_puzzle(day("1"), false).await.unwrap();
+        assert_eq!(log.lock().unwrap().len(), 1);
+        runner.get_puzzle(day("1"), true).await.unwrap();
+        assert_eq!(log.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn puzzle_is_downloaded_again_once_part_1_is_solved() {
+        let (url, log) = stand_in(&[("/2022/day/1", PART_2_PAGE)]);
+        let runner = runner("solved", url, false);
+        std::fs::create_dir_all(runner.cache_dir()).unwrap();
+        std::fs::write(runner.puzzle_path(day("1")), PART_1_PAGE).unwrap();
+        let solved = Submission::new(day("1"), Part::One, "24000".to_string(), Verdict::Correct);
+        runner
+            .submissions()
+            .unwrap()
+            .record(solved.unwrap())
+            .unwrap();
+
+        let html = runner.get_puzzle(day("1"), false).await.unwrap();
+        assert_eq!(puzzle::revealed_parts(&html), 2);
+        assert_eq!(log.lock().unwrap().len(), 1);
+        runner.get_puzzle(day("1"), false).await.unwrap();
+        assert_eq!(log.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn scrapes_examples_from_served_puzzle() {
+        let (url, log) = stand_in(&[("/2022/day/1", PART_2_PAGE)]);
+        let runner = runner("examples", url, false);
+
+        let examples = runner.get_examples(day("1"), false).await.unwrap();
+        let answers = examples
+            .iter()
+            .map(|example| (example.part, example.answer.as_deref()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            answers,
+            [(Part::One, Some("24000")), (Part::Two, Some("45000"))]
+        );
+        assert!(examples[0].input.starts_with("1000\n2000\n3000\n\n4000\n"));
+        assert_eq!(examples[1].input, examples[0].input);
+
+        runner.get_examples(day("1"), false).await.unwrap();
+        assert_eq!(log.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn logged_out_puzzle_is_not_cached() {
+        let (url, _) = stand_in(&[(
+            "/2022/day/1",
+            "<main><article class=\"day-desc\"></article>\
+             <p>To play, please identify yourself via one of these services:</p></main>",
+        )]);
+        let runner = runner("puzzle-expired", url, false);
+
+        let error = runner.get_puzzle(day("1"), false).await.err().unwrap();
+        assert_eq!(error.downcast_ref(), Some(&InputError::SessionExpired));
+        assert!(!runner.puzzle_path(day("1")).exists());
+    }
+
+    #[test]
+    fn reads_input_from_any_file() {
+        let path = env::temp_dir().join(format!("aoc-read-input-{}.txt", process::id()));
+        std::fs::write(&path, "A Y\nB X\n").unwrap();
+        let lines = read_input(&path).unwrap();
+        assert_eq!(lines.collect::<Vec<_>>(), ["A Y", "B X"]);
+        std::fs::remove_file(&path).unwrap();
+
+        let error = read_input(&path).err().unwrap();
+        assert!(error.to_string().starts_with("Can't read input from "));
+    }
+
+    #[test]
+    fn reads_input_from_stdin() {
+        // Nothing is read until the lines are, but a file named `-` would fail here
+        assert!(read_input(Path::new("-")).is_ok());
+    }
+
+    #[tokio::test]
+    async fn records_rate_limit_as_cooldown() {
+        let (url, _) = stand_in(&[(
+            "/2022/day/1/answer",
+            include_str!("../tests/fixtures/answer/rate_limited.html"),
+        )]);
+        let runner = runner("rate-limited", url, false);
+
+        let verdict = runner
+            .send_result(day("1"), Part::One, "24000".to_string())
+            .await
+            .unwrap();
+        let wait = Duration::from_secs(4 * 60 + 12);
+        assert_eq!(verdict, Verdict::RateLimited { wait });
+        let cooldown = runner.submissions().unwrap().cooldown(day("1")).unwrap();
+        assert!(cooldown <= wait && cooldown > wait - Duration::from_secs(5));
+        assert!(runner.submissions().unwrap().cooldown(day("2")).is_none());
+    }
+
+    #[test]
+    fn checks_input() {
+        let ok = StatusCode::OK;
+        assert_eq!(check_input(ok, Some("text/plain"), "1000\n2000\n"), Ok(()));
+        assert_eq!(
+            check_input(ok, Some("text/html; charset=utf-8"), "<p>Log in</p>"),
+            Err(InputError::SessionExpired)
+        );
+        assert_eq!(
+            check_input(
+                StatusCode::BAD_REQUEST,
+                None,
+                "Puzzle inputs differ by user."
+            ),
+            Err(InputError::SessionExpired)
+        );
+        assert_eq!(
+            check_input(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                None,
+                "Internal Server Error"
+            ),
+            Err(InputError::Unexpected(
+                "HTTP status 500 Internal Server Error".to_string()
+            ))
+        );
+        assert!(check_input(ok, None, "\n").is_err());
+    }
+
+    #[test]
+    fn parses_global_options_anywhere() {
+        for args in [
+            ["aoc", "--year", "2021", "run-all"],
+            ["aoc", "run-all", "--year", "2021"],
+        ] {
+            let opts = Opts::try_parse_args(args).unwrap();
+            assert!(matches!(opts.command, Some(Command::RunAll)));
+            assert_eq!(opts.year, Some(parse_year("2021").unwrap()));
+        }
+        let opts = Opts::try_parse_args(["aoc", "--offline", "--format", "tsv", "verify"]).unwrap();
+        assert!(opts.offline);
+        assert_eq!(opts.format, Some(Format::Tsv));
+
+        let opts = Opts::try_parse_args(["aoc", "--year", "2021", "-d", "1"]).unwrap();
+        assert_eq!(opts.run.day, Some(day("1")));
+        assert!(opts.command.is_none());
+    }
+
+    #[test]
+    fn refuses_run_options_with_subcommand() {
+        for args in [
+            &["aoc", "-d", "1", "run-all"][..],
+            &["aoc", "--part", "both", "verify"],
+            &["aoc", "--send", "-p", "1", "run-all"],
+        ] {
+            let error = Opts::try_parse_args(args).unwrap_err();
+            assert_eq!(error.kind(), clap::error::ErrorKind::ArgumentConflict);
+        }
+        assert!(Opts::try_parse_args(["aoc"]).is_err());
+    }
+
+    #[test]
+    fn unlock_times() {
+        let year = parse_year("2022").unwrap();
+        assert_eq!(
+            year.unlock_time(day("1")).to_rfc3339(),
+            "2022-12-01T05:00:00+00:00"
+        );
+        let now = Utc.with_ymd_and_hms(2022, 12, 6, 4, 59, 59).unwrap();
+        assert_eq!(year.unlocked_days(now).count(), 5);
+        let now = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+        assert_eq!(year.unlocked_days(now).count(), 25);
+
+        let year = parse_year("2025").unwrap();
+        let now = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+        assert_eq!(year.unlocked_days(now).count(), 12);
+    }
+
+    #[tokio::test]
+    async fn offline_never_connects() {
+        let (url, log) = stand_in(&[("/2022/day/1/input", "1\n")]);
+        let runner = runner("offline", url, true);
+
+        assert!(runner.get_input(day("1"), false).await.is_err());
+        assert!(log.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn sends_answer_and_records_verdict() {
+        let (url, log) = stand_in(&[(
+            "/2022/day/1/answer",
+            include_str!("../tests/fixtures/answer/correct.html"),
+        )]);
+        let runner = runner("answer", url, false);
+
+        let verdict = runner
+            .send_result(day("1"), Part::One, "24000".to_string())
+            .await
+            .unwrap();
+        assert_eq!(verdict, Verdict::Correct);
+        let received = log.lock().unwrap().clone();
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0].method, "POST");
+        assert_eq!(received[0].path, "/2022/day/1/answer");
+        assert_eq!(received[0].body, "level=1&answer=24000");
+        assert_eq!(received[0].cookie.as_deref(), Some("session=test-session"));
+        assert!(runner.submissions().unwrap().is_solved(day("1"), Part::One));
+
+        // Already solved, so it isn't sent again
+        assert!(runner
+            .send_result(day("1"), Part::One, "24000".to_string())
+            .await
+            .is_err());
+        assert_eq!(log.lock().unwrap().len(), 1);
     }
 }
@@ -0,0 +1,96 @@
+use crate::runner::{Day, Part, Year};
+use crate::{day_1, day_2, day_3, day_4, day_5, day_6, Lines};
+use anyhow::{anyhow, bail, Result};
+use itertools::Itertools;
+use std::any::TypeId;
+use std::fmt::{Display, Formatter};
+
+pub trait Solution {
+    type Input: 'static;
+    type Output1: Display;
+    /// Set to [`Unsolved`] and leave out `part_2` until the second part is done.
+    type Output2: 'static + Display;
+
+    fn parse(lines: Lines) -> Result<Self::Input>;
+    fn part_1(input: &Self::Input) -> Result<Self::Output1>;
+
+    fn part_2(_input: &Self::Input) -> Result<Self::Output2> {
+        bail!("Part 2 is not implemented yet")
+    }
+}
+
+/// Output of a part which has not been solved yet.
+pub enum Unsolved {}
+
+impl Display for Unsolved {
+    fn fmt(&self, _f: &mut Formatter<'_>) -> std::fmt::Result {
+        match *self {}
+    }
+}
+
+/// Input that has already gone through [`Solution::parse`], ready to be solved
+/// any number of times.
+pub trait Parsed {
+    fn is_implemented(&self, part: Part) -> bool;
+    fn solve(&self, part: Part) -> Result<String>;
+}
+
+struct ParsedInput<S: Solution>(S::Input);
+
+impl<S: Solution> Parsed for ParsedInput<S> {
+    fn is_implemented(&self, part: Part) -> bool {
+        match part {
+            Part::One => true,
+            Part::Two => TypeId::of::<S::Output2>() != TypeId::of::<Unsolved>(),
+        }
+    }
+
+    fn solve(&self, part: Part) -> Result<String> {
+        match part {
+            Part::One => S::part_1(&self.0).map(|output| output.to_string()),
+            Part::Two => S::part_2(&self.0).map(|output| output.to_string()),
+        }
+    }
+}
+
+pub type Parser = fn(Lines) -> Result<Box<dyn Parsed>>;
+
+pub fn parse<S: Solution + 'static>(lines: Lines) -> Result<Box<dyn Parsed>> {
+    Ok(Box::new(ParsedInput::<S>(S::parse(lines)?)))
+}
+
+// Solved parts are told apart by `Parsed::is_implemented`, so entries are per day
+const SOLUTIONS: &[(u16, u8, Parser)] = &[
+    (2022, 1, parse::<day_1::Day1>),
+    (2022, 2, parse::<day_2::Day2>),
+    (2022, 3, parse::<day_3::Day3>),
+    (2022, 4, parse::<day_4::Day4>),
+    (2022, 5, parse::<day_5::Day5>),
+    (2022, 6, parse::<day_6::Day6>),
+];
+
+pub fn find(year: Year, day: Day) -> Result<Parser> {
+    let implemented = SOLUTIONS
+        .iter()
+        .filter(|(year_number, _, _)| *year_number == year.year_number())
+        .map(|(_, day_number, parser)| (*day_number, *parser))
+        .collect::<Vec<_>>();
+    if implemented.is_empty() {
+        bail!("No days of {} are implemented yet", year.year_number());
+    }
+    implemented
+        .iter()
+        .find(|(day_number, _)| *day_number == day.day_number())
+        .map(|(_, parser)| *parser)
+        .ok_or_else(|| {
+            anyhow!(
+                "Day {} of {} is not implemented. Implemented days: {}",
+                day.day_number(),
+                year.year_number(),
+                implemented
+                    .iter()
+                    .map(|(day_number, _)| day_number)
+                    .join(", ")
+            )
+        })
+}
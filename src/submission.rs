@@ -0,0 +1,513 @@
+use crate::runner::{parse_day, Day, Part};
+use anyhow::{anyhow, bail, Result};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use scraper::{Html, Selector};
+use std::fmt::{Display, Formatter};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Hint {
+    TooHigh,
+    TooLow,
+}
+
+/// What the site said about a submitted answer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    Incorrect { hint: Option<Hint> },
+    RateLimited { wait: Duration },
+    AlreadySolved,
+    WrongLevel,
+}
+
+static WAIT_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"You have (?:(\d+)h )?(?:(\d+)m )?(\d+)s left to wait").unwrap());
+
+impl Verdict {
+    /// Parses the HTML returned after posting an answer for `part`.
+    ///
+    /// The site answers with the same "wrong level" message both when the part is
+    /// already solved and when it isn't unlocked yet. Part 1 is always unlocked,
+    /// so for it the message can only mean it was solved already.
+    pub fn parse(html: &str, part: Part) -> Result<Self> {
+        let document = Html::parse_document(html);
+        let selector = Selector::parse("body > main > article > p:nth-child(1)").unwrap();
+        let text = document
+            .select(&selector)
+            .next()
+            .ok_or_else(|| anyhow!("No content!"))?
+            .text()
+            .collect::<String>();
+
+        if text.contains("That's the right answer") {
+            Ok(Verdict::Correct)
+        } else if text.contains("That's not the right answer") {
+            let hint = if text.contains("too high") {
+                Some(Hint::TooHigh)
+            } else if text.contains("too low") {
+                Some(Hint::TooLow)
+            } else {
+                None
+            };
+            Ok(Verdict::Incorrect { hint })
+        } else if text.contains("You gave an answer too recently") {
+            let captures = WAIT_REGEX
+                .captures(&text)
+                .ok_or_else(|| anyhow!("Unknown wait time: {text}"))?;
+            let number =
+                |i| -> Result<u64> { Ok(captures.get(i).map_or(Ok(0), |m| m.as_str().parse())?) };
+            let seconds = number(1)? * 3600 + number(2)? * 60 + number(3)?;
+            Ok(Verdict::RateLimited {
+                wait: Duration::from_secs(seconds),
+            })
+        } else if text.contains("You don't seem to be solving the right level") {
+            match part {
+                Part::One => Ok(Verdict::AlreadySolved),
+                Part::Two => Ok(Verdict::WrongLevel),
+            }
+        } else {
+            bail!("Unknown response: {text}")
+        }
+    }
+
+    /// Short form used in the submission history file.
+    fn encode(&self) -> String {
+        match self {
+            Verdict::Correct => "correct".to_string(),
+            Verdict::Incorrect { hint: None } => "incorrect".to_string(),
+            Verdict::Incorrect {
+                hint: Some(Hint::TooHigh),
+            } => "too-high".to_string(),
+            Verdict::Incorrect {
+                hint: Some(Hint::TooLow),
+            } => "too-low".to_string(),
+            Verdict::RateLimited { wait } => format!("rate-limited:{}", wait.as_secs()),
+            Verdict::AlreadySolved => "already-solved".to_string(),
+            Verdict::WrongLevel => "wrong-level".to_string(),
+        }
+    }
+
+    fn decode(s: &str) -> Result<Self> {
+        Ok(match s {
+            "correct" => Verdict::Correct,
+            "incorrect" => Verdict::Incorrect { hint: None },
+            "too-high" => Verdict::Incorrect {
+                hint: Some(Hint::TooHigh),
+            },
+            "too-low" => Verdict::Incorrect {
+                hint: Some(Hint::TooLow),
+            },
+            "already-solved" => Verdict::AlreadySolved,
+            "wrong-level" => Verdict::WrongLevel,
+            _ => {
+                let seconds = s
+                    .strip_prefix("rate-limited:")
+                    .ok_or_else(|| anyhow!("Unknown verdict: {s}"))?;
+                Verdict::RateLimited {
+                    wait: Duration::from_secs(seconds.parse()?),
+                }
+            }
+        })
+    }
+
+    /// Process exit code reported for this verdict, so scripts can tell them apart.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Verdict::Correct => 0,
+            Verdict::Incorrect { .. } => 2,
+            Verdict::RateLimited { .. } => 3,
+            Verdict::AlreadySolved => 4,
+            Verdict::WrongLevel => 5,
+        }
+    }
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "Correct"),
+            Verdict::Incorrect { hint: None } => write!(f, "Incorrect"),
+            Verdict::Incorrect {
+                hint: Some(Hint::TooHigh),
+            } => write!(f, "Incorrect, too high"),
+            Verdict::Incorrect {
+                hint: Some(Hint::TooLow),
+            } => write!(f, "Incorrect, too low"),
+            Verdict::RateLimited { wait } => write!(f, "Rate limited for {wait:?}"),
+            Verdict::AlreadySolved => write!(f, "Already solved"),
+            Verdict::WrongLevel => write!(f, "Wrong level"),
+        }
+    }
+}
+
+pub struct Submission {
+    pub timestamp: u64,
+    pub day: Day,
+    pub part: Part,
+    pub answer: String,
+    pub verdict: Verdict,
+}
+
+impl Submission {
+    pub fn new(day: Day, part: Part, answer: String, verdict: Verdict) -> Result<Self> {
+        Ok(Self {
+            timestamp: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+            day,
+            part,
+            answer,
+            verdict,
+        })
+    }
+
+    fn to_line(&self) -> String {
+        [
+            self.timestamp.to_string(),
+            self.day.day_number().to_string(),
+            self.part.part_number().to_string(),
+            self.verdict.encode(),
+            self.answer.clone(),
+        ]
+        .join("\t")
+    }
+
+    fn from_line(line: &str) -> Result<Self> {
+        let fields = line.splitn(5, '\t').collect::<Vec<_>>();
+        let [timestamp, day, part, verdict, answer] = fields[..] else {
+            bail!("Invalid submission history line: {line}");
+        };
+        Ok(Self {
+            timestamp: timestamp.parse()?,
+            day: parse_day(day).map_err(|e| anyhow!(e))?,
+            part: part.parse().map_err(|e: &str| anyhow!(e))?,
+            answer: answer.to_string(),
+            verdict: Verdict::decode(verdict)?,
+        })
+    }
+
+    /// How long ago the answer was sent, e.g. `3m ago`.
+    pub fn age(&self) -> String {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(self.timestamp, |now| now.as_secs());
+        let seconds = now.saturating_sub(self.timestamp);
+        match seconds {
+            0..=59 => format!("{seconds}s ago"),
+            60..=3599 => format!("{}m ago", seconds / 60),
+            3600..=86399 => format!("{}h ago", seconds / 3600),
+            _ => format!("{}d ago", seconds / 86400),
+        }
+    }
+}
+
+/// Exclusive bounds on a numeric answer.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Bounds {
+    pub lower: Option<i128>,
+    pub upper: Option<i128>,
+}
+
+impl Bounds {
+    pub fn contains(&self, number: i128) -> bool {
+        self.lower.is_none_or(|lower| number > lower)
+            && self.upper.is_none_or(|upper| number < upper)
+    }
+
+    pub fn is_unbounded(&self) -> bool {
+        self.lower.is_none() && self.upper.is_none()
+    }
+}
+
+impl Display for Bounds {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match (self.lower, self.upper) {
+            (Some(lower), Some(upper)) => {
+                write!(f, "greater than {lower} and less than {upper}")
+            }
+            (Some(lower), None) => write!(f, "greater than {lower}"),
+            (None, Some(upper)) => write!(f, "less than {upper}"),
+            (None, None) => write!(f, "unbounded"),
+        }
+    }
+}
+
+/// Every answer sent so far, stored one per line so it survives between runs.
+pub struct Submissions {
+    path: PathBuf,
+    submissions: Vec<Submission>,
+}
+
+impl Submissions {
+    pub fn load(path: &Path) -> Result<Self> {
+        let submissions = if path.exists() {
+            fs::read_to_string(path)?
+                .lines()
+                .map(Submission::from_line)
+                .collect::<Result<_>>()?
+        } else {
+            Vec::new()
+        };
+        Ok(Self {
+            path: path.to_path_buf(),
+            submissions,
+        })
+    }
+
+    pub fn attempts(&self, day: Day, part: Part) -> impl Iterator<Item = &Submission> {
+        self.submissions
+            .iter()
+            .filter(move |submission| submission.day == day && submission.part == part)
+    }
+
+    pub fn is_solved(&self, day: Day, part: Part) -> bool {
+        self.attempts(day, part).any(|submission| {
+            matches!(
+                submission.verdict,
+                Verdict::Correct | Verdict::AlreadySolved
+            )
+        })
+    }
+
+    /// Range left for numeric answers after the "too high" and "too low" hints.
+    pub fn bounds(&self, day: Day, part: Part) -> Bounds {
+        let mut bounds = Bounds::default();
+        for submission in self.attempts(day, part) {
+            let Ok(answer) = submission.answer.parse::<i128>() else {
+                continue;
+            };
+            match submission.verdict {
+                Verdict::Incorrect {
+                    hint: Some(Hint::TooHigh),
+                } => bounds.upper = Some(bounds.upper.map_or(answer, |upper| upper.min(answer))),
+                Verdict::Incorrect {
+                    hint: Some(Hint::TooLow),
+                } => bounds.lower = Some(bounds.lower.map_or(answer, |lower| lower.max(answer))),
+                _ => {}
+            }
+        }
+        bounds
+    }
+
+    /// Time left until answers for `day` are accepted again, judging by the last
+    /// rate limited submission.
+    pub fn cooldown(&self, day: Day) -> Option<Duration> {
+        let (timestamp, wait) = self
+            .submissions
+            .iter()
+            .rev()
+            .filter(|submission| submission.day == day)
+            .find_map(|submission| match submission.verdict {
+                Verdict::RateLimited { wait } => Some((submission.timestamp, wait)),
+                _ => None,
+            })?;
+        let end = UNIX_EPOCH + Duration::from_secs(timestamp) + wait;
+        end.duration_since(SystemTime::now()).ok()
+    }
+
+    /// Fails if sending `answer` is known to be pointless.
+    pub fn check(&self, day: Day, part: Part, answer: &str) -> Result<()> {
+        for submission in self.attempts(day, part) {
+            match submission.verdict {
+                Verdict::Correct | Verdict::AlreadySolved => bail!(
+                    "Day {} part {} is already solved with {}",
+                    day.day_number(),
+                    part.part_number(),
+                    submission.answer
+                ),
+                Verdict::Incorrect { .. } if submission.answer == answer => bail!(
+                    "{answer} was already rejected for day {} part {} ({}, {})",
+                    day.day_number(),
+                    part.part_number(),
+                    submission.verdict,
+                    submission.age()
+                ),
+                _ => {}
+            }
+        }
+
+        // Non-numeric answers, like day 5's crate labels, can't be too high or too low
+        if let Ok(number) = answer.parse::<i128>() {
+            let bounds = self.bounds(day, part);
+            if !bounds.contains(number) {
+                bail!("{answer} can't be right, the answer is {bounds}");
+            }
+        }
+        Ok(())
+    }
+
+    pub fn record(&mut self, submission: Submission) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", submission.to_line())?;
+        self.submissions.push(submission);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(html: &str) -> Verdict {
+        Verdict::parse(html, Part::One).unwrap()
+    }
+
+    #[test]
+    fn correct() {
+        let html = include_str!("../tests/fixtures/answer/correct.html");
+        assert_eq!(parse(html), Verdict::Correct);
+    }
+
+    #[test]
+    fn incorrect() {
+        let html = include_str!("../tests/fixtures/answer/too_high.html");
+        assert_eq!(
+            parse(html),
+            Verdict::Incorrect {
+                hint: Some(Hint::TooHigh)
+            }
+        );
+        let html = include_str!("../tests/fixtures/answer/too_low.html");
+        assert_eq!(
+            parse(html),
+            Verdict::Incorrect {
+                hint: Some(Hint::TooLow)
+            }
+        );
+        let html = include_str!("../tests/fixtures/answer/incorrect.html");
+        assert_eq!(parse(html), Verdict::Incorrect { hint: None });
+    }
+
+    #[test]
+    fn rate_limited() {
+        let html = include_str!("../tests/fixtures/answer/rate_limited.html");
+        assert_eq!(
+            parse(html),
+            Verdict::RateLimited {
+                wait: Duration::from_secs(4 * 60 + 12)
+            }
+        );
+    }
+
+    #[test]
+    fn wrong_level() {
+        let html = include_str!("../tests/fixtures/answer/wrong_level.html");
+        assert_eq!(parse(html), Verdict::AlreadySolved);
+        assert_eq!(
+            Verdict::parse(html, Part::Two).unwrap(),
+            Verdict::WrongLevel
+        );
+    }
+
+    #[test]
+    fn submission_round_trip() {
+        let line = "1670400000\t5\t2\trate-limited:252\tRWLWGJGFD";
+        let submission = Submission::from_line(line).unwrap();
+        assert_eq!(
+            submission.verdict,
+            Verdict::RateLimited {
+                wait: Duration::from_secs(252)
+            }
+        );
+        assert_eq!(submission.to_line(), line);
+    }
+
+    #[test]
+    fn refuses_pointless_answers() {
+        let day = parse_day("1").unwrap();
+        let submission = |part, answer: &str, verdict| Submission {
+            timestamp: 0,
+            day,
+            part,
+            answer: answer.to_string(),
+            verdict,
+        };
+        let submissions = Submissions {
+            path: PathBuf::new(),
+            submissions: vec![
+                submission(Part::One, "100", Verdict::Incorrect { hint: None }),
+                submission(Part::One, "200", Verdict::Correct),
+                submission(Part::Two, "300", Verdict::Incorrect { hint: None }),
+            ],
+        };
+
+        assert!(submissions.check(day, Part::One, "123").is_err());
+        assert!(submissions.check(day, Part::Two, "300").is_err());
+        assert!(submissions.check(day, Part::Two, "301").is_ok());
+    }
+
+    #[test]
+    fn cooldown() {
+        let day = parse_day("1").unwrap();
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let submission = |timestamp, day, verdict| Submission {
+            timestamp,
+            day,
+            part: Part::One,
+            answer: "100".to_string(),
+            verdict,
+        };
+        let rate_limited = |seconds| Verdict::RateLimited {
+            wait: Duration::from_secs(seconds),
+        };
+        let mut submissions = Submissions {
+            path: PathBuf::new(),
+            submissions: vec![submission(now - 600, day, rate_limited(60))],
+        };
+        assert_eq!(submissions.cooldown(day), None);
+
+        submissions.submissions.extend([
+            submission(now - 10, day, rate_limited(60)),
+            submission(now - 5, day, Verdict::Incorrect { hint: None }),
+        ]);
+        let cooldown = submissions.cooldown(day).unwrap();
+        assert!(cooldown > Duration::from_secs(45) && cooldown <= Duration::from_secs(50));
+        assert_eq!(submissions.cooldown(parse_day("2").unwrap()), None);
+    }
+
+    #[test]
+    fn bounds() {
+        let day = parse_day("1").unwrap();
+        let submission = |answer: &str, hint| Submission {
+            timestamp: 0,
+            day,
+            part: Part::One,
+            answer: answer.to_string(),
+            verdict: Verdict::Incorrect { hint: Some(hint) },
+        };
+        let submissions = Submissions {
+            path: PathBuf::new(),
+            submissions: vec![
+                submission("500", Hint::TooHigh),
+                submission("100", Hint::TooLow),
+                submission("300", Hint::TooHigh),
+                submission("50", Hint::TooLow),
+            ],
+        };
+
+        assert_eq!(
+            submissions.bounds(day, Part::One),
+            Bounds {
+                lower: Some(100),
+                upper: Some(300)
+            }
+        );
+        assert!(submissions.check(day, Part::One, "100").is_err());
+        assert!(submissions.check(day, Part::One, "400").is_err());
+        assert!(submissions.check(day, Part::One, "101").is_ok());
+        assert!(submissions.check(day, Part::One, "CMZ").is_ok());
+        assert!(submissions.bounds(day, Part::Two).is_unbounded());
+    }
+}
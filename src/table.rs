@@ -0,0 +1,102 @@
+use itertools::Itertools;
+use std::str::FromStr;
+
+/// How tables are printed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Markdown,
+    /// Tab separated, for piping into other tools
+    Tsv,
+}
+
+impl Format {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Format::Markdown => "markdown",
+            Format::Tsv => "tsv",
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "markdown" | "md" => Ok(Format::Markdown),
+            "tsv" => Ok(Format::Tsv),
+            _ => Err("The format must be either 'markdown' or 'tsv'"),
+        }
+    }
+}
+
+pub fn table(format: Format, headers: &[&str], rows: &[Vec<String>]) -> String {
+    match format {
+        Format::Markdown => markdown_table(headers, rows),
+        Format::Tsv => [headers.join("\t")]
+            .into_iter()
+            .chain(rows.iter().map(|row| row.join("\t")))
+            .join("\n"),
+    }
+}
+
+/// Formats rows as a markdown table with padded columns, so it reads well both
+/// in the terminal and pasted into a README.
+fn markdown_table(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let widths = headers
+        .iter()
+        .enumerate()
+        .map(|(i, header)| {
+            rows.iter()
+                .map(|row| row[i].chars().count())
+                .chain([header.chars().count(), 3])
+                .max()
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    let format_row = |cells: Vec<String>| {
+        let cells = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:width$}"))
+            .join(" | ");
+        format!("| {cells} |")
+    };
+
+    let mut lines = vec![
+        format_row(headers.iter().map(|header| header.to_string()).collect()),
+        format_row(widths.iter().map(|width| "-".repeat(*width)).collect()),
+    ];
+    lines.extend(rows.iter().map(|row| format_row(row.clone())));
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pads_columns() {
+        let rows = vec![
+            vec!["1".to_string(), "24000".to_string()],
+            vec!["12".to_string(), "7".to_string()],
+        ];
+        assert_eq!(
+            markdown_table(&["Day", "Answer"], &rows),
+            "| Day | Answer |
+| --- | ------ |
+| 1   | 24000  |
+| 12  | 7      |"
+        );
+    }
+
+    #[test]
+    fn tab_separated() {
+        let rows = vec![vec!["1".to_string(), "24000".to_string()]];
+        assert_eq!(
+            table(Format::Tsv, &["Day", "Answer"], &rows),
+            "Day\tAnswer\n1\t24000"
+        );
+    }
+}
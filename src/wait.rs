@@ -0,0 +1,102 @@
+use crate::runner::{Day, InputError, RunOpts, Runner, WaitOpts};
+use crate::{countdown, run, solution};
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use std::time::Duration;
+use tokio::time::sleep;
+
+/// How many times the input is requested before giving up.
+const ATTEMPTS: u32 = 6;
+
+/// Waits until the puzzle unlocks, downloads its input right away and runs the
+/// solution if asked to.
+pub async fn wait(runner: &Runner, opts: WaitOpts) -> Result<()> {
+    let year = runner.year();
+    let day = match opts.day {
+        Some(day) => day,
+        None => year
+            .days()
+            .find(|day| year.unlock_time(*day) > Utc::now())
+            .ok_or_else(|| {
+                anyhow!(
+                    "Every day of {} is unlocked already, choose one with --day",
+                    year.year_number()
+                )
+            })?,
+    };
+    // Better to find out about a missing solution before the wait than after it
+    if opts.run {
+        solution::find(year, day)?;
+    }
+
+    let unlock_time = year.unlock_time(day);
+    if let Ok(left) = (unlock_time - Utc::now()).to_std() {
+        println!(
+            "Day {} unlocks at {}",
+            day.day_number(),
+            unlock_time.format("%Y-%m-%d %H:%M:%S UTC")
+        );
+        countdown(left).await?;
+    }
+    fetch(runner, day, Duration::from_secs(1)).await?;
+
+    if !opts.run {
+        return Ok(());
+    }
+    let run_opts = RunOpts {
+        day: Some(day),
+        part: opts.part,
+        force_download: false,
+        input: None,
+        send: false,
+        example: false,
+        force: false,
+        wait: false,
+    };
+    run(runner, run_opts).await
+}
+
+/// Downloads the input, trying again with growing delays while the site doesn't
+/// have it yet, since its clock can be a little behind ours.
+async fn fetch(runner: &Runner, day: Day, first_delay: Duration) -> Result<()> {
+    let mut delay = first_delay;
+    let mut attempt = 1;
+    loop {
+        match runner.get_input(day, false).await {
+            Ok(_) => return Ok(()),
+            Err(error)
+                if attempt < ATTEMPTS
+                    && error.downcast_ref::<InputError>() == Some(&InputError::NotFound) =>
+            {
+                println!("Not available yet, trying again in {delay:?}");
+                sleep(delay).await;
+                delay *= 2;
+                attempt += 1;
+            }
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::tests::{day, runner, stand_in};
+
+    #[tokio::test]
+    async fn retries_until_available() {
+        let (url, log) = stand_in(&[("/2022/day/1/input", "1\n")]);
+        let runner = runner("wait", url, false);
+        fetch(&runner, day("1"), Duration::from_millis(1))
+            .await
+            .unwrap();
+        assert_eq!(log.lock().unwrap().len(), 1);
+
+        let error = fetch(&runner, day("2"), Duration::from_millis(1))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(error.downcast_ref(), Some(&InputError::NotFound));
+        assert_eq!(log.lock().unwrap().len(), 1 + ATTEMPTS as usize);
+    }
+}